@@ -0,0 +1,154 @@
+//! Redraw time of the book list on a synthetic library.
+//!
+//! Run with `cargo bench --bench draw`, or pass a book count with
+//! `cargo bench --bench draw -- 200000`.
+
+use anyhow::Result;
+use calibre_tui::config::FilterConfig;
+use calibre_tui::facet::FacetPanel;
+use calibre_tui::filter::BookSearch;
+use calibre_tui::group::TableRows;
+use calibre_tui::layout::Layout;
+use calibre_tui::line_edit::LineEditor;
+use calibre_tui::theme::Theme;
+use calibre_tui::ui::{self, DisplayMode, DrawState, TableGeometry};
+use calibre_tui::utils::book::Book;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::widgets::TableState;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const DEFAULT_BOOK_COUNT: usize = 100_000;
+const FRAMES: usize = 200;
+const WIDTH: u16 = 160;
+const HEIGHT: u16 = 48;
+const WORDS: &[&str] = &[
+  "river", "night", "garden", "stone", "winter", "empire", "silver", "harbor", "shadow", "orchid",
+  "lantern", "voyage", "ember", "meadow", "citadel", "falcon", "glass", "thunder", "willow",
+  "archive",
+];
+const NAMES: &[&str] = &[
+  "Ada", "Boris", "Chen", "Dana", "Emil", "Farah", "Goro", "Hanna", "Ivan", "Julia",
+];
+const TAGS: &[&str] = &[
+  "Fiction.Fantasy",
+  "Fiction.Science Fiction",
+  "History",
+  "Poetry",
+  "Travel",
+  "Fiction.Mystery",
+];
+
+fn main() -> Result<()> {
+  let book_count = std::env::args()
+    .skip(1)
+    .find_map(|arg| arg.parse().ok())
+    .unwrap_or(DEFAULT_BOOK_COUNT);
+
+  let books = (0..book_count).map(synthetic_book).collect::<Vec<_>>();
+  let layout = Layout::default();
+  let started = Instant::now();
+  let search = BookSearch::new(&books, &FilterConfig::default(), &layout)?;
+  println!("indexed {book_count} books in {:.2?}", started.elapsed());
+
+  let theme = Theme::default();
+  for query in ["", "river"] {
+    let results = search.search(query)?;
+    let rows = TableRows::flat(&results);
+    for display in [DisplayMode::Table, DisplayMode::Card, DisplayMode::Grid] {
+      let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT))?;
+      let input = LineEditor::new(query.to_string());
+      let mut facets = FacetPanel::default();
+      let mut table_state = TableState::default();
+      let mut table_geometry = TableGeometry::default();
+      let selected_book_indices = BTreeSet::new();
+
+      let mut elapsed = Duration::ZERO;
+      for frame_index in 0..FRAMES {
+        // Spread the focused row over the whole list, so deep offsets are measured too.
+        table_state.select((!rows.is_empty()).then(|| frame_index * rows.len() / FRAMES));
+        let started = Instant::now();
+        terminal.draw(|frame| {
+          ui::draw(
+            frame,
+            frame.area(),
+            DrawState {
+              input: &input,
+              books: &books,
+              results: &results,
+              rows: &rows,
+              facets: &mut facets,
+              table_state: &mut table_state,
+              selected_book_indices: &selected_book_indices,
+              layout: &layout,
+              display,
+              covers: None,
+              theme: &theme,
+              prompt: None,
+              command_completion: None,
+              key_hints: &[],
+              key_help_entries: None,
+              key_help_title: "",
+              message: None,
+              sort_label: "title asc",
+              mode_label: None,
+              picker: None,
+              view_label: None,
+              library_label: None,
+              table_geometry: &mut table_geometry,
+            },
+          );
+        })?;
+        elapsed += started.elapsed();
+      }
+
+      println!(
+        "{:<5} query {:<8} {:>7} rows: {:.3} ms per redraw",
+        display.name(),
+        format!("{query:?}"),
+        rows.len(),
+        elapsed.as_secs_f64() * 1000.0 / FRAMES as f64
+      );
+    }
+  }
+  Ok(())
+}
+
+fn synthetic_book(index: usize) -> Book {
+  let word = |offset: usize| WORDS[(index / (offset + 1) + offset * 7) % WORDS.len()];
+  let title = format!("The {} of {} {}", word(0), word(1), index);
+  let authors = (0..1 + index % 3)
+    .map(|author| {
+      format!(
+        "{} {}",
+        NAMES[(index + author) % NAMES.len()],
+        word(author + 2)
+      )
+    })
+    .collect();
+  Book {
+    id: index as i64,
+    path: PathBuf::from(format!("/library/{index}")),
+    title_sort: title.trim_start_matches("The ").to_string(),
+    title,
+    authors,
+    author_sort: String::new(),
+    series: if index.is_multiple_of(4) {
+      format!("{} Saga", word(3))
+    } else {
+      String::new()
+    },
+    series_index: (index % 12 + 1) as f64,
+    formats: vec!["EPUB".to_string(), "PDF".to_string()][..1 + index % 2].to_vec(),
+    tags: (0..index % 4)
+      .map(|tag| TAGS[(index + tag) % TAGS.len()].to_string())
+      .collect(),
+    publisher: format!("{} Press", word(4)),
+    languages: vec!["eng".to_string()],
+    pubdate: "2019-05-01 12:00:00+00:00".to_string(),
+    timestamp: "2024-01-15 08:30:00+00:00".to_string(),
+    cover: None,
+  }
+}
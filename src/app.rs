@@ -1,13 +1,24 @@
-use crate::config::{Config, OpenConfig};
+use crate::config::{Config, OpenConfig, SessionConfig};
+use crate::cover::CoverCache;
+use crate::facet::{FacetPanel, toggle_field_filter};
 use crate::filter::{BookSearch, SearchResult};
-use crate::layout::{BookField, Layout};
-use crate::sort::{SortSpec, sort_results};
+use crate::group::{TableRows, can_group_by};
+use crate::history::{History, HistoryCursor, HistoryKind, history_picker};
+use crate::keymap::Keymaps;
+use crate::layout::{BookField, ColumnKey, DEFAULT_VIEW, Layout, Layouts, save_layout};
+use crate::line_edit::{EditResult, LineEditor};
+use crate::picker::{Picker, PickerItem};
+use crate::saved_search::{SavedKind, SearchCatalog};
+use crate::session::Session;
+use crate::sort::{SortDirection, SortSpec, sort_results};
 use crate::theme::Theme;
-use crate::ui;
+use crate::ui::{self, DisplayMode, TableGeometry};
 use crate::utils::book::Book;
 use crate::utils::db::load_books_from_db;
-use anyhow::{Context, Result};
-use crossterm::event::{self, Event, KeyEvent, KeyEventKind, KeyModifiers, MouseEventKind};
+use anyhow::{Context, Result, anyhow};
+use crossterm::event::{
+  self, Event, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use framework_tui::{
   CommandCompletion, CommandState, KeyBindings, KeyContext, KeyDispatcher, KeyHelpEntry,
   MatchResult, Prompt, PromptInputResult, current_word_start, filter_completion_candidates,
@@ -16,34 +27,110 @@ use framework_tui::{
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 use ratatui::widgets::TableState;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io::{Stdout, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::time::Duration;
-
-const COMMAND_NAMES: &[&str] = &["help", "sort"];
-const SORT_FIELDS: &[&str] = &["title", "authors", "series", "formats", "tags"];
+use std::time::{Duration, Instant};
+
+const COMMAND_NAMES: &[&str] = &[
+  "column",
+  "delete-library",
+  "delete-search",
+  "display",
+  "group",
+  "help",
+  "layout",
+  "library",
+  "save-library",
+  "save-search",
+  "search",
+  "sort",
+  "view",
+];
+const SORT_FIELDS: &[&str] = &[
+  "title",
+  "authors",
+  "series",
+  "formats",
+  "tags",
+  "publisher",
+  "languages",
+];
+const COLUMN_ACTIONS: &[&str] = &["hide", "move", "show", "width"];
 const SORT_DIRECTIONS: &[&str] = &["asc", "desc"];
+const SORT_MODIFIERS: &[&str] = &["relevance", "nogroup"];
+const GROUP_FIELDS: &[&str] = &[
+  "authors",
+  "series",
+  "formats",
+  "tags",
+  "publisher",
+  "languages",
+  "off",
+];
+const SORT_OPTIONS: &[&str] = &["natural", "casefold", "noaccent", "pinyin", "kana"];
+/// Sort option that only applies to title and authors.
+const DISPLAY_SORT_OPTION: &str = "display";
+/// Longest gap between two clicks on the same row that counts as a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+/// Largest normal-mode count, so held digit keys cannot overflow.
+const MAX_COUNT: usize = 99_999;
 
 pub struct App {
   books: Vec<Book>,
   search: BookSearch,
-  keymap: KeyBindings,
+  keymaps: Keymaps,
+  mode: Mode,
+  /// Normal-mode count typed before an action; 0 when none.
+  count: usize,
   key_dispatcher: KeyDispatcher,
+  /// Dispatches search box editing keys in the `input` context.
+  input_dispatcher: KeyDispatcher,
   open_config: OpenConfig,
+  /// Active view, a working copy of its entry in `layouts`.
   layout: Layout,
+  layouts: Layouts,
+  view: String,
+  display: DisplayMode,
+  /// Cover thumbnails for the grid; `None` when `display.covers` is off.
+  covers: Option<CoverCache>,
   theme: Theme,
-  input: String,
+  input: LineEditor,
   results: Vec<SearchResult>,
+  rows: TableRows,
+  group_field: Option<BookField>,
+  collapsed_groups: BTreeSet<String>,
+  facets: FacetPanel,
   table_state: TableState,
+  table_geometry: TableGeometry,
+  /// Time and row of the last left click, for double-click detection.
+  last_click: Option<(Instant, usize)>,
+  /// Row where the last plain or Ctrl click happened; Shift-click selects from here.
+  click_anchor: Option<usize>,
+  /// Visible column whose right border is being dragged.
+  resizing_column: Option<usize>,
   selected_book_indices: BTreeSet<usize>,
   exit_on_open: bool,
   output_paths: Vec<PathBuf>,
+  /// Book list rows per page in the current display, from the last draw.
   page_size: usize,
   sort_spec: SortSpec,
   prompt: Option<Prompt>,
   command_state: CommandState,
+  history: History,
+  history_limit: usize,
+  /// Up/Down recall position in the search history.
+  search_history: HistoryCursor,
+  /// The query was just typed or recalled, so `input` history keys recall queries before
+  /// browser keys move the list. Moving through the list ends it.
+  search_editing: bool,
+  picker: Option<(PickerTarget, Picker)>,
+  catalog: SearchCatalog,
+  /// Active virtual library name.
+  library: Option<String>,
+  /// Books in the active virtual library; results outside it are hidden.
+  library_books: Option<HashSet<usize>>,
   key_help: bool,
   message: Option<String>,
 }
@@ -53,13 +140,33 @@ enum EventAction {
   Quit,
 }
 
+/// What a picker popup lists and where the picked value goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PickerTarget {
+  SearchHistory,
+  CommandHistory,
+  SavedSearch,
+  Library,
+  View,
+}
+
+/// Browser input mode. `Normal` and `Insert` are used when modal navigation is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+  Browse,
+  Normal,
+  Insert,
+}
+
 impl App {
   pub fn new(
     config: Config,
-    keymap: KeyBindings,
-    layout: Layout,
+    keymaps: Keymaps,
+    layouts: Layouts,
     theme: Theme,
     exit_on_open: bool,
+    session: Option<Session>,
+    mut history: History,
   ) -> Result<Self> {
     let books = load_books_from_db(&config.library_path).with_context(|| {
       format!(
@@ -67,34 +174,166 @@ impl App {
         config.library_path.display()
       )
     })?;
+    let layout = layouts
+      .get(DEFAULT_VIEW)
+      .cloned()
+      .context("missing default view")?;
     let search =
       BookSearch::new(&books, &config.filter, &layout).context("failed to build search index")?;
+    let sort_spec = match &layout.sort {
+      Some(sort_spec) => sort_spec.clone(),
+      None => config.sort.default_spec()?,
+    };
+    let catalog =
+      SearchCatalog::load(&config.library_path).context("failed to load saved searches")?;
+    history.truncate(config.history.max_entries);
+    let mut command_state = CommandState::default();
+    for command in &history.command {
+      command_state.push_history(command.clone());
+    }
 
     let mut app = Self {
       books,
       search,
-      keymap,
+      mode: if keymaps.modal {
+        Mode::Normal
+      } else {
+        Mode::Browse
+      },
+      keymaps,
+      count: 0,
       key_dispatcher: KeyDispatcher::default(),
+      input_dispatcher: KeyDispatcher::default(),
       open_config: config.open.clone(),
       layout,
+      layouts,
+      view: DEFAULT_VIEW.to_string(),
+      display: DisplayMode::default(),
+      covers: config.display.covers.then(CoverCache::default),
       theme,
-      input: String::new(),
+      input: LineEditor::default(),
       results: Vec::new(),
+      rows: TableRows::default(),
+      group_field: None,
+      collapsed_groups: BTreeSet::new(),
+      facets: FacetPanel::default(),
       table_state: TableState::default(),
+      table_geometry: TableGeometry::default(),
+      last_click: None,
+      click_anchor: None,
+      resizing_column: None,
       selected_book_indices: BTreeSet::new(),
       exit_on_open,
       output_paths: Vec::new(),
       page_size: 20,
-      sort_spec: SortSpec::default(),
+      sort_spec,
       prompt: None,
-      command_state: CommandState::default(),
+      command_state,
+      history,
+      history_limit: config.history.max_entries,
+      search_history: HistoryCursor::default(),
+      search_editing: true,
+      picker: None,
+      catalog,
+      library: None,
+      library_books: None,
       key_help: false,
       message: None,
     };
-    app.refresh_results()?;
+    match session {
+      Some(session) => app.restore_session(session, &config.session)?,
+      None => app.refresh_results()?,
+    }
+    if !app.catalog.skipped.is_empty() {
+      app.set_message(format!(
+        "not imported from Calibre (unsupported search syntax): {}",
+        app.catalog.skipped.join(", ")
+      ));
+    }
+    if let Some(error) = &app.catalog.calibre_error {
+      app.set_message(format!("Calibre saved searches ignored: {error}"));
+    }
     Ok(app)
   }
 
+  fn restore_session(&mut self, session: Session, config: &SessionConfig) -> Result<()> {
+    if config.restore_query {
+      self.input = LineEditor::new(session.query);
+    }
+    if config.restore_view
+      && let Some(name) = session.view
+    {
+      self.set_view(&name)?;
+    }
+    if config.restore_display
+      && let Some(display) = session.display.as_deref().and_then(DisplayMode::parse)
+    {
+      self.display = display;
+    }
+    if config.restore_sort && !session.sort.trim().is_empty() {
+      match SortSpec::parse(&session.sort.split_whitespace().collect::<Vec<_>>()) {
+        Ok(sort_spec) => self.sort_spec = sort_spec,
+        Err(error) => self.set_message(format!("previous sort ignored: {error}")),
+      }
+    }
+    if config.restore_library
+      && let Some(name) = session.library
+    {
+      self.set_library(Some(name))?;
+    }
+    self.refresh_results()?;
+
+    let book_indices_by_id = self
+      .books
+      .iter()
+      .enumerate()
+      .map(|(book_index, book)| (book.id, book_index))
+      .collect::<HashMap<_, _>>();
+    if config.restore_selection {
+      self.selected_book_indices = session
+        .selected_book_ids
+        .iter()
+        .filter_map(|id| book_indices_by_id.get(id).copied())
+        .collect();
+    }
+    if config.restore_cursor
+      && let Some(book_index) = session
+        .focused_book_id
+        .and_then(|id| book_indices_by_id.get(&id).copied())
+      && let Some(row_index) = self.row_of_book(book_index)
+    {
+      self.table_state.select(Some(row_index));
+      *self.table_state.offset_mut() = session.scroll_offset.min(row_index);
+    }
+    Ok(())
+  }
+
+  /// Browser state to restore on the next launch.
+  pub fn session(&self) -> Session {
+    Session {
+      query: self.input.text().to_string(),
+      sort: self.sort_spec.command_args(),
+      library: self.library.clone(),
+      view: (self.view != DEFAULT_VIEW).then(|| self.view.clone()),
+      display: (self.display != DisplayMode::Table).then(|| self.display.name().to_string()),
+      focused_book_id: self
+        .current_book_index()
+        .map(|book_index| self.books[book_index].id),
+      selected_book_ids: self
+        .selected_book_indices
+        .iter()
+        .filter_map(|book_index| self.books.get(*book_index))
+        .map(|book| book.id)
+        .collect(),
+      scroll_offset: self.table_state.offset(),
+    }
+  }
+
+  /// Search and command history to save for the next launch.
+  pub fn history(&self) -> &History {
+    &self.history
+  }
+
   pub fn run(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<Vec<PathBuf>> {
     self.draw(terminal)?;
 
@@ -104,6 +343,7 @@ impl App {
       if event::poll(Duration::from_millis(250))? {
         let action = self.handle_event(event::read()?)?;
         if matches!(action, EventAction::Quit) {
+          self.record_search();
           return Ok(std::mem::take(&mut self.output_paths));
         }
         should_draw = true;
@@ -111,6 +351,7 @@ impl App {
         while event::poll(Duration::from_millis(0))? {
           let action = self.handle_event(event::read()?)?;
           if matches!(action, EventAction::Quit) {
+            self.record_search();
             return Ok(std::mem::take(&mut self.output_paths));
           }
           should_draw = true;
@@ -125,11 +366,12 @@ impl App {
 
   fn draw(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
     terminal.draw(|frame| {
-      self.page_size = usize::from(frame.area().height.saturating_sub(8)).max(1);
       let key_help_entries = self
         .key_help
         .then(|| self.key_help_entries())
         .unwrap_or_default();
+      let key_help_title = self.key_help_title();
+      let mode_label = self.mode_label();
       ui::draw(
         frame,
         frame.area(),
@@ -137,18 +379,29 @@ impl App {
           input: &self.input,
           books: &self.books,
           results: &self.results,
+          rows: &self.rows,
+          facets: &mut self.facets,
           table_state: &mut self.table_state,
           selected_book_indices: &self.selected_book_indices,
           layout: &self.layout,
+          display: self.display,
+          covers: self.covers.as_mut(),
           theme: &self.theme,
           prompt: self.prompt.as_ref(),
           command_completion: self.command_state.completion(),
           key_hints: self.key_dispatcher.hints(),
           key_help_entries: self.key_help.then_some(key_help_entries.as_slice()),
+          key_help_title,
           message: self.message.as_deref(),
           sort_label: &self.sort_spec.label(),
+          mode_label: mode_label.as_deref(),
+          table_geometry: &mut self.table_geometry,
+          picker: self.picker.as_ref().map(|(_, picker)| picker),
+          view_label: (self.view != DEFAULT_VIEW).then_some(self.view.as_str()),
+          library_label: self.library.as_deref(),
         },
       );
+      self.page_size = self.table_geometry.page_size();
     })?;
     Ok(())
   }
@@ -159,6 +412,10 @@ impl App {
       return Ok(EventAction::Continue);
     }
 
+    if self.picker.is_some() {
+      return self.handle_picker_event(event);
+    }
+
     if self.prompt.is_some() {
       return self.handle_prompt_event(event);
     }
@@ -171,29 +428,50 @@ impl App {
             self.key_dispatcher.clear();
             return Ok(EventAction::Continue);
           }
-          match self
-            .key_dispatcher
-            .dispatch(&self.keymap, KeyContext::Browser, token)
-          {
-            MatchResult::Action(action) => return self.handle_action(&action),
+          if self.mode == Mode::Normal && !had_pending_key_sequence && self.push_count_digit(&key) {
+            return Ok(EventAction::Continue);
+          }
+          if !had_pending_key_sequence && self.recall_search_key(&token)? {
+            return Ok(EventAction::Continue);
+          }
+          match self.key_dispatcher.dispatch(
+            mode_bindings(&self.keymaps, self.mode),
+            KeyContext::Browser,
+            token.clone(),
+          ) {
+            MatchResult::Action(action) => {
+              if ends_search_editing(&action) {
+                self.search_editing = false;
+              }
+              return self.handle_action(&action);
+            }
             MatchResult::Prefix(_) => return Ok(EventAction::Continue),
-            MatchResult::None if had_pending_key_sequence => return Ok(EventAction::Continue),
-            MatchResult::None => {}
+            MatchResult::None if had_pending_key_sequence => {
+              self.count = 0;
+              return Ok(EventAction::Continue);
+            }
+            MatchResult::None => self.count = 0,
+          }
+          if self.mode != Mode::Normal && self.edit_search(token)? {
+            return Ok(EventAction::Continue);
           }
         }
 
-        if is_search_input_key(&key)
+        if self.mode != Mode::Normal
+          && is_search_input_key(&key)
           && let crossterm::event::KeyCode::Char(ch) = key.code
         {
-          self.input.push(ch);
+          self.input.insert_char(ch);
+          self.search_editing = true;
           self.refresh_results()?;
         }
       }
-      Event::Mouse(mouse) => match mouse.kind {
-        MouseEventKind::ScrollDown => self.next_item(),
-        MouseEventKind::ScrollUp => self.previous_item(),
-        _ => {}
-      },
+      Event::Paste(value) if self.mode != Mode::Normal => {
+        self.input.insert_str(&value);
+        self.search_editing = true;
+        self.refresh_results()?;
+      }
+      Event::Mouse(mouse) => return self.handle_mouse(mouse),
       Event::Resize(_, _) => {}
       _ => {}
     }
@@ -201,6 +479,189 @@ impl App {
     Ok(EventAction::Continue)
   }
 
+  fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<EventAction> {
+    let (x, y) = (mouse.column, mouse.row);
+    match mouse.kind {
+      MouseEventKind::ScrollDown => {
+        self.search_editing = false;
+        self.next_item();
+      }
+      MouseEventKind::ScrollUp => {
+        self.search_editing = false;
+        self.previous_item();
+      }
+      MouseEventKind::Down(MouseButton::Left) => {
+        if let Some(column) = self.table_geometry.border_at(x, y) {
+          self.resizing_column = Some(column);
+        } else if let Some(column) = self.table_geometry.header_at(x, y) {
+          self.sort_by_column(column)?;
+        } else if let Some(row) = self
+          .table_geometry
+          .row_at(x, y)
+          .filter(|row| *row < self.rows.len())
+        {
+          return self.click_row(row, mouse.modifiers);
+        }
+      }
+      MouseEventKind::Drag(MouseButton::Left) => {
+        if let Some(column) = self.resizing_column {
+          self.resize_column(column, x);
+        }
+      }
+      MouseEventKind::Up(MouseButton::Left) => self.resizing_column = None,
+      _ => {}
+    }
+    Ok(EventAction::Continue)
+  }
+
+  /// Focuses a clicked row. Ctrl toggles its selection, Shift selects the range from the
+  /// last clicked row, and a double-click opens the book or toggles a group.
+  fn click_row(&mut self, row: usize, modifiers: KeyModifiers) -> Result<EventAction> {
+    let now = Instant::now();
+    let double_click = self
+      .last_click
+      .is_some_and(|(time, last_row)| last_row == row && now.duration_since(time) <= DOUBLE_CLICK);
+    self.last_click = (!double_click).then_some((now, row));
+    self.facets.focused = false;
+    self.search_editing = false;
+
+    if modifiers.contains(KeyModifiers::SHIFT) {
+      let anchor = self
+        .click_anchor
+        .or(self.table_state.selected())
+        .unwrap_or(row);
+      for range_row in anchor.min(row)..=anchor.max(row) {
+        if let Some(result) = self
+          .rows
+          .result_index(range_row)
+          .and_then(|result_index| self.results.get(result_index))
+        {
+          self.selected_book_indices.insert(result.book_index);
+        }
+      }
+      self.table_state.select(Some(row));
+      return Ok(EventAction::Continue);
+    }
+
+    self.table_state.select(Some(row));
+    self.click_anchor = Some(row);
+    if modifiers.contains(KeyModifiers::CONTROL) {
+      if let Some(book_index) = self.current_book_index()
+        && !self.selected_book_indices.insert(book_index)
+      {
+        self.selected_book_indices.remove(&book_index);
+      }
+    } else if double_click {
+      // Opens the clicked book only; selected books are left for `open`.
+      match self.current_book_index() {
+        Some(book_index) => {
+          self.record_search();
+          self.open_book(&self.books[book_index])?;
+          if self.exit_on_open {
+            return Ok(EventAction::Quit);
+          }
+        }
+        None => self.toggle_group(),
+      }
+    }
+    Ok(EventAction::Continue)
+  }
+
+  /// Sorts by a clicked header column, switching direction when it is already the first key.
+  fn sort_by_column(&mut self, column: usize) -> Result<()> {
+    let Some(column) = self
+      .layout
+      .visible_columns()
+      .nth(column)
+      .map(|column| column.key.clone())
+    else {
+      return Ok(());
+    };
+    let direction = match self.sort_spec.primary_key() {
+      Some(key) if key.column == column && key.direction == SortDirection::Asc => "desc",
+      _ => "asc",
+    };
+    self.execute_sort_command(vec![&column.name(), direction])
+  }
+
+  /// Moves the right border of a visible column to `x`, taking the space from its right
+  /// neighbour. Widths become cell counts, which keeps their proportions.
+  fn resize_column(&mut self, column: usize, x: u16) {
+    let columns = &self.table_geometry.columns;
+    let (Some((start, width)), Some((_, next_width))) =
+      (columns.get(column), columns.get(column + 1))
+    else {
+      return;
+    };
+    let pair_width = width + next_width;
+    if pair_width < 2 {
+      return;
+    }
+    let new_width = x.saturating_sub(*start).clamp(1, pair_width - 1);
+    let mut widths = columns.iter().map(|(_, width)| *width).collect::<Vec<_>>();
+    widths[column] = new_width;
+    widths[column + 1] = pair_width - new_width;
+    self.layout.set_visible_widths(&widths);
+    self.table_geometry.columns = widths
+      .iter()
+      .scan(columns[0].0, |x, width| {
+        let span = (*x, *width);
+        *x += width;
+        Some(span)
+      })
+      .collect();
+  }
+
+  /// Applies an `input` keymap editing action to the search box. Returns `false` when the
+  /// key has no editing binding there.
+  fn edit_search(&mut self, token: String) -> Result<bool> {
+    let action = match self.input_dispatcher.dispatch(
+      mode_bindings(&self.keymaps, self.mode),
+      KeyContext::Input,
+      token,
+    ) {
+      MatchResult::Action(action) => action,
+      MatchResult::Prefix(_) | MatchResult::None => {
+        self.input_dispatcher.clear();
+        return Ok(false);
+      }
+    };
+
+    match self.input.apply(&action) {
+      EditResult::Changed => {
+        self.search_editing = true;
+        self.refresh_results()?;
+      }
+      EditResult::Unchanged => {}
+      EditResult::NotEditing => return Ok(false),
+    }
+    Ok(true)
+  }
+
+  /// Recalls a query with the `input` history keys while the search box is being edited in
+  /// the browser. Returns `false` when nothing was recalled, so the key can move the list.
+  fn recall_search_key(&mut self, token: &str) -> Result<bool> {
+    if self.mode != Mode::Browse || !self.search_editing || self.facets.focused {
+      return Ok(false);
+    }
+    let action = match self.input_dispatcher.dispatch(
+      mode_bindings(&self.keymaps, self.mode),
+      KeyContext::Input,
+      token.to_string(),
+    ) {
+      MatchResult::Action(action) => action,
+      MatchResult::Prefix(_) | MatchResult::None => {
+        self.input_dispatcher.clear();
+        return Ok(false);
+      }
+    };
+    match action.as_str() {
+      "history_previous" => self.recall_search(true),
+      "history_next" => self.recall_search(false),
+      _ => Ok(false),
+    }
+  }
+
   fn handle_prompt_event(&mut self, event: Event) -> Result<EventAction> {
     match event {
       Event::Key(key) => {
@@ -208,7 +669,12 @@ impl App {
           let Some(prompt) = self.prompt.as_mut() else {
             return Ok(EventAction::Continue);
           };
-          handle_prompt_key(prompt, &mut self.command_state, &self.keymap, key)
+          handle_prompt_key(
+            prompt,
+            &mut self.command_state,
+            mode_bindings(&self.keymaps, self.mode),
+            key,
+          )
         };
 
         match result {
@@ -225,6 +691,9 @@ impl App {
           PromptInputResult::UnknownAction(action) if action == "help" => {
             self.key_help = true;
           }
+          PromptInputResult::UnknownAction(action) if action == "history_search" => {
+            self.open_picker(PickerTarget::CommandHistory);
+          }
           PromptInputResult::EditInEditor { .. } => {
             self.set_message("$EDITOR command editing is not available here");
           }
@@ -244,15 +713,278 @@ impl App {
     Ok(EventAction::Continue)
   }
 
+  /// Handles keys while a picker popup is open, through the `input` keymap section. Editing
+  /// actions and typing change the filter; movement and history actions select a match.
+  fn handle_picker_event(&mut self, event: Event) -> Result<EventAction> {
+    let Some((_, picker)) = self.picker.as_mut() else {
+      return Ok(EventAction::Continue);
+    };
+
+    match event {
+      Event::Key(key) => {
+        let Some(token) = key_event_to_token(key) else {
+          return Ok(EventAction::Continue);
+        };
+        let changed = match self.input_dispatcher.dispatch(
+          mode_bindings(&self.keymaps, self.mode),
+          KeyContext::Input,
+          token,
+        ) {
+          MatchResult::Action(action) => match action.as_str() {
+            "cancel" => {
+              self.picker = None;
+              return Ok(EventAction::Continue);
+            }
+            "submit" => {
+              self.accept_picker()?;
+              return Ok(EventAction::Continue);
+            }
+            "help" => {
+              self.key_help = true;
+              return Ok(EventAction::Continue);
+            }
+            "move_up" | "history_previous" | "completion_previous" => {
+              picker.previous();
+              false
+            }
+            "move_down" | "history_next" | "completion_next" | "history_search" => {
+              picker.next();
+              false
+            }
+            _ => picker.query.apply(&action) == EditResult::Changed,
+          },
+          MatchResult::Prefix(_) => false,
+          MatchResult::None => {
+            self.input_dispatcher.clear();
+            let crossterm::event::KeyCode::Char(ch) = key.code else {
+              return Ok(EventAction::Continue);
+            };
+            if !is_search_input_key(&key) {
+              return Ok(EventAction::Continue);
+            }
+            picker.query.insert_char(ch);
+            true
+          }
+        };
+        if changed {
+          picker.refresh();
+        }
+      }
+      Event::Paste(value) => {
+        picker.query.insert_str(&value);
+        picker.refresh();
+      }
+      _ => {}
+    }
+
+    Ok(EventAction::Continue)
+  }
+
+  fn open_picker(&mut self, target: PickerTarget) {
+    let picker = match target {
+      PickerTarget::SearchHistory => history_picker(&self.history, HistoryKind::Search),
+      PickerTarget::CommandHistory => history_picker(&self.history, HistoryKind::Command),
+      PickerTarget::SavedSearch => self.catalog.picker(SavedKind::Search),
+      PickerTarget::Library => self.catalog.picker(SavedKind::Library),
+      PickerTarget::View => self.view_picker(),
+    };
+    self.input_dispatcher.clear();
+    self.picker = Some((target, picker));
+  }
+
+  fn accept_picker(&mut self) -> Result<()> {
+    let Some((target, picker)) = self.picker.take() else {
+      return Ok(());
+    };
+    let Some(value) = picker.selected_item().map(|item| item.value.clone()) else {
+      return Ok(());
+    };
+
+    match target {
+      PickerTarget::SearchHistory => {
+        self.input.set_text(value);
+        self.search_history.reset();
+        self.refresh_results()
+      }
+      PickerTarget::CommandHistory => {
+        self.prompt = Some(Prompt::command(value));
+        self.refresh_command_completion();
+        Ok(())
+      }
+      PickerTarget::SavedSearch => self.apply_saved_search(&value),
+      PickerTarget::Library if value.is_empty() => self.set_library(None),
+      PickerTarget::Library => self.set_library(Some(value)),
+      PickerTarget::View => self.set_view(&value),
+    }
+  }
+
+  fn view_picker(&self) -> Picker {
+    let items = self
+      .layouts
+      .names()
+      .into_iter()
+      .map(|name| PickerItem {
+        label: name.to_string(),
+        detail: if name == self.view {
+          "(current)".to_string()
+        } else {
+          String::new()
+        },
+        value: name.to_string(),
+      })
+      .collect();
+    Picker::new("Views", items)
+  }
+
+  /// Switches to another layout view. Runtime column changes stay with the view being left
+  /// until quit. The search only runs again when the searchable fields differ.
+  fn set_view(&mut self, name: &str) -> Result<()> {
+    let Some(layout) = self.layouts.get(name).cloned() else {
+      self.set_message(format!("unknown view: {name}"));
+      return Ok(());
+    };
+    let previous = std::mem::replace(&mut self.layout, layout);
+    self.layouts.set(&self.view, previous.clone());
+    self.view = name.to_string();
+    if let Some(sort_spec) = &self.layout.sort {
+      self.sort_spec = sort_spec.clone();
+    }
+
+    let focused = self.current_book_index();
+    if !self.layout.same_search(&previous) {
+      self.refresh_search_fields()?;
+    } else {
+      self.sort_results(focused);
+    }
+    self.set_message(format!("view: {name}"));
+    Ok(())
+  }
+
+  /// Re-runs the search and the virtual library after the searchable fields changed,
+  /// keeping the focused book.
+  fn refresh_search_fields(&mut self) -> Result<()> {
+    let focused = self.current_book_index();
+    self.search.set_search_fields(&self.books, &self.layout)?;
+    match self.library.clone() {
+      Some(name) => self.set_library(Some(name))?,
+      None => self.refresh_results()?,
+    }
+    if let Some(row) = focused.and_then(|book_index| self.row_of_book(book_index)) {
+      self.table_state.select(Some(row));
+    }
+    Ok(())
+  }
+
+  /// Replaces the query with a saved search.
+  fn apply_saved_search(&mut self, name: &str) -> Result<()> {
+    let Some(query) = self
+      .catalog
+      .get(SavedKind::Search, name)
+      .map(str::to_string)
+    else {
+      self.set_message(format!("unknown saved search: {name}"));
+      return Ok(());
+    };
+    self.record_search();
+    self.input.set_text(query);
+    self.refresh_results()
+  }
+
+  /// Sets the virtual library whose query filters results under the search box query.
+  fn set_library(&mut self, name: Option<String>) -> Result<()> {
+    let Some(name) = name else {
+      self.library = None;
+      self.library_books = None;
+      self.set_message("library: all books");
+      return self.refresh_results();
+    };
+    let Some(query) = self.catalog.get(SavedKind::Library, &name) else {
+      self.set_message(format!("unknown library: {name}"));
+      return Ok(());
+    };
+
+    let books = match self.search.search(query) {
+      Ok(results) => results
+        .into_iter()
+        .map(|result| result.book_index)
+        .collect(),
+      Err(error) => {
+        self.set_message(format!("invalid query for library {name}: {error}"));
+        return Ok(());
+      }
+    };
+    self.library_books = Some(books);
+    self.set_message(format!("library: {name}"));
+    self.library = Some(name);
+    self.refresh_results()
+  }
+
+  /// Replaces the search query with an older or newer history entry.
+  /// Returns whether an entry was recalled.
+  fn recall_search(&mut self, older: bool) -> Result<bool> {
+    let entries = &self.history.search;
+    let entry = if older {
+      self.search_history.previous(entries, self.input.text())
+    } else {
+      self.search_history.next(entries, self.input.text())
+    };
+    let Some(entry) = entry else {
+      return Ok(false);
+    };
+    self.input.set_text(entry);
+    self.search_editing = true;
+    self.refresh_results()?;
+    Ok(true)
+  }
+
+  /// Saves the current query to the search history.
+  fn record_search(&mut self) {
+    self
+      .history
+      .push(HistoryKind::Search, self.input.text(), self.history_limit);
+    self.search_history.reset();
+  }
+
+  /// Runs an action, repeating movements by the normal-mode count. With a count,
+  /// `jump_start` and `jump_end` go to that row instead.
   fn handle_action(&mut self, action: &str) -> Result<EventAction> {
-    if action.starts_with("sort ") {
+    let count = std::mem::take(&mut self.count);
+    if count > 0 && matches!(action, "jump_start" | "jump_end") {
+      self.jump_to_row(count - 1);
+      return Ok(EventAction::Continue);
+    }
+
+    let repeat = if is_repeatable_action(action) {
+      count.max(1)
+    } else {
+      1
+    };
+    for _ in 0..repeat {
+      if matches!(self.run_action(action)?, EventAction::Quit) {
+        return Ok(EventAction::Quit);
+      }
+    }
+    Ok(EventAction::Continue)
+  }
+
+  fn run_action(&mut self, action: &str) -> Result<EventAction> {
+    if [
+      "sort ", "group ", "search ", "library ", "view ", "display ",
+    ]
+    .iter()
+    .any(|command| action.starts_with(command))
+    {
       self.execute_command(action)?;
       return Ok(EventAction::Continue);
     }
+    if self.facets.focused && self.handle_facet_action(action)? {
+      return Ok(EventAction::Continue);
+    }
 
     match action {
       "quit" => return Ok(EventAction::Quit),
       "open" => {
+        self.record_search();
         if self.open_selected()? {
           return Ok(EventAction::Quit);
         }
@@ -265,20 +997,56 @@ impl App {
           .collect();
         return Ok(EventAction::Quit);
       }
-      "copy_paths" => self.copy_paths_to_clipboard(),
+      "copy_paths" => {
+        self.record_search();
+        self.copy_paths_to_clipboard();
+      }
       "move_up" => self.previous_item(),
       "move_down" => self.next_item(),
+      "move_left" => self.step_item(false),
+      "move_right" => self.step_item(true),
       "page_up" => self.page_up(),
       "page_down" => self.page_down(),
       "jump_start" => self.jump_start(),
       "jump_end" => self.jump_end(),
       "toggle_selection" => self.toggle_selected(),
       "select_all" => self.select_all_results(),
+      "toggle_group" => self.toggle_group(),
+      "toggle_facets" => self.toggle_facets(),
+      "switch_focus" => self.switch_focus(),
       "clear_selection" => self.clear_selection(),
       "delete_input" => {
-        self.input.pop();
+        if self.input.apply("backspace") == EditResult::Changed {
+          self.search_editing = true;
+          self.refresh_results()?;
+        }
+      }
+      "search" => {
+        self.record_search();
+        self.search_editing = true;
+        self.input.set_text(String::new());
         self.refresh_results()?;
+        if self.keymaps.modal {
+          self.set_mode(Mode::Insert);
+        }
+      }
+      "normal_mode" => {
+        self.record_search();
+        self.set_mode(Mode::Normal);
       }
+      "insert_mode" => self.set_mode(Mode::Insert),
+      "history_previous" => {
+        self.recall_search(true)?;
+      }
+      "history_next" => {
+        self.recall_search(false)?;
+      }
+      "history_search" => self.open_picker(PickerTarget::SearchHistory),
+      "saved_searches" => self.open_picker(PickerTarget::SavedSearch),
+      "libraries" => self.open_picker(PickerTarget::Library),
+      "views" => self.open_picker(PickerTarget::View),
+      "next_match" => self.jump_to_match(true),
+      "previous_match" => self.jump_to_match(false),
       "command" => self.start_command(),
       "help" => self.key_help = true,
       other => self.set_message(format!("unknown action: {other}")),
@@ -288,16 +1056,78 @@ impl App {
   }
 
   fn refresh_results(&mut self) -> Result<()> {
-    self.results = self.search.search(&self.input)?;
+    self.results = self.search.search(self.input.text())?;
+    if let Some(library_books) = &self.library_books {
+      self
+        .results
+        .retain(|result| library_books.contains(&result.book_index));
+    }
     self.sort_results(None);
-    if self.results.is_empty() {
+    if self.rows.is_empty() {
       self.table_state.select(None);
     } else {
       self.table_state.select(Some(0));
     }
+    self.refresh_facets();
     Ok(())
   }
 
+  fn refresh_facets(&mut self) {
+    if self.facets.visible {
+      self
+        .facets
+        .refresh(&self.books, &self.results, self.input.text());
+    }
+  }
+
+  /// Handles navigation and selection while the facet panel has focus. Returns `false` for
+  /// actions that keep their browser meaning.
+  fn handle_facet_action(&mut self, action: &str) -> Result<bool> {
+    match action {
+      "move_up" => self.facets.previous(),
+      "move_down" => self.facets.next(),
+      "page_up" => self.facets.page_up(self.facet_page_size()),
+      "page_down" => self.facets.page_down(self.facet_page_size()),
+      "jump_start" => self.facets.jump_start(),
+      "jump_end" => self.facets.jump_end(),
+      "toggle_group" => self.facets.toggle(),
+      "open" => self.apply_facet()?,
+      _ => return Ok(false),
+    }
+    Ok(true)
+  }
+
+  /// Adds or removes a filter for the value under the facet cursor, or expands and
+  /// collapses a category header.
+  fn apply_facet(&mut self) -> Result<()> {
+    let Some((field, value)) = self
+      .facets
+      .selected_value()
+      .map(|(field, value)| (field, value.value.clone()))
+    else {
+      self.facets.toggle();
+      return Ok(());
+    };
+
+    let query = toggle_field_filter(self.input.text(), field, &value);
+    self.input.set_text(query);
+    self.refresh_results()
+  }
+
+  fn toggle_facets(&mut self) {
+    self.facets.visible = !self.facets.visible;
+    self.facets.focused = self.facets.visible;
+    self.refresh_facets();
+  }
+
+  fn switch_focus(&mut self) {
+    if self.facets.visible {
+      self.facets.focused = !self.facets.focused;
+    } else {
+      self.toggle_facets();
+    }
+  }
+
   fn sort_results(&mut self, preserve_book_index: Option<usize>) {
     sort_results(
       &mut self.results,
@@ -305,44 +1135,117 @@ impl App {
       &self.sort_spec,
       &self.layout,
     );
+    self.rebuild_rows();
     if let Some(book_index) = preserve_book_index
-      && let Some(row_index) = self
-        .results
-        .iter()
-        .position(|result| result.book_index == book_index)
+      && let Some(row_index) = self.row_of_book(book_index)
     {
       self.table_state.select(Some(row_index));
     }
   }
 
+  fn rebuild_rows(&mut self) {
+    self.rows = match self.group_field {
+      Some(field) => TableRows::grouped(&self.results, &self.books, field, &self.collapsed_groups),
+      None => TableRows::flat(&self.results),
+    };
+  }
+
+  fn row_of_book(&self, book_index: usize) -> Option<usize> {
+    (0..self.rows.len()).find(|row| {
+      self
+        .rows
+        .result_index(*row)
+        .and_then(|result_index| self.results.get(result_index))
+        .is_some_and(|result| result.book_index == book_index)
+    })
+  }
+
+  fn toggle_group(&mut self) {
+    let Some(value) = self
+      .table_state
+      .selected()
+      .and_then(|row| self.rows.group_at(row))
+      .map(|group| group.value.clone())
+    else {
+      return;
+    };
+
+    if !self.collapsed_groups.insert(value.clone()) {
+      self.collapsed_groups.remove(&value);
+    }
+    self.rebuild_rows();
+    self.table_state.select(self.rows.header_row(&value));
+  }
+
+  /// Moves focus up one row, or one tile row in the grid, wrapping around.
   fn previous_item(&mut self) {
-    if self.results.is_empty() {
+    if self.rows.is_empty() {
       self.table_state.select(None);
       return;
     }
 
+    let step = self.grid_step();
+    let last = self.rows.len() - 1;
     let index = match self.table_state.selected() {
-      Some(0) | None => self.results.len() - 1,
-      Some(index) => index.saturating_sub(1),
+      Some(index) if index >= step => index - step,
+      // Wrap to the same grid column on the last tile row, or to the last row.
+      Some(index) => (last - last % step + index).min(last),
+      None => last,
     };
     self.table_state.select(Some(index));
   }
 
+  /// Moves focus down one row, or one tile row in the grid, wrapping around.
   fn next_item(&mut self) {
-    if self.results.is_empty() {
+    if self.rows.is_empty() {
       self.table_state.select(None);
       return;
     }
 
+    let step = self.grid_step();
     let index = match self.table_state.selected() {
-      Some(index) if index + 1 < self.results.len() => index + 1,
+      Some(index) if index + step < self.rows.len() => index + step,
+      // A shorter last tile row still has a tile below the focused one.
+      Some(index) if index / step < (self.rows.len() - 1) / step => self.rows.len() - 1,
+      Some(index) => index % step,
+      None => 0,
+    };
+    self.table_state.select(Some(index));
+  }
+
+  /// Moves focus to the previous or next row in reading order, which is left or right in
+  /// the grid.
+  fn step_item(&mut self, forward: bool) {
+    if self.rows.is_empty() {
+      self.table_state.select(None);
+      return;
+    }
+
+    let last = self.rows.len() - 1;
+    let index = match self.table_state.selected() {
+      Some(index) if forward && index < last => index + 1,
+      Some(0) | None if !forward => last,
+      Some(index) if !forward => index - 1,
       _ => 0,
     };
     self.table_state.select(Some(index));
   }
 
+  /// Rows per grid line; 1 in the table and card displays.
+  fn grid_step(&self) -> usize {
+    match self.display {
+      DisplayMode::Grid => self.table_geometry.grid_columns.max(1),
+      DisplayMode::Table | DisplayMode::Card => 1,
+    }
+  }
+
+  /// Facet rows per page, the height of the book list body.
+  fn facet_page_size(&self) -> usize {
+    usize::from(self.table_geometry.body.height).max(1)
+  }
+
   fn page_up(&mut self) {
-    if self.results.is_empty() {
+    if self.rows.is_empty() {
       self.table_state.select(None);
       return;
     }
@@ -356,12 +1259,12 @@ impl App {
   }
 
   fn page_down(&mut self) {
-    if self.results.is_empty() {
+    if self.rows.is_empty() {
       self.table_state.select(None);
       return;
     }
 
-    let last = self.results.len() - 1;
+    let last = self.rows.len() - 1;
     let index = self
       .table_state
       .selected()
@@ -372,7 +1275,7 @@ impl App {
   }
 
   fn jump_start(&mut self) {
-    if self.results.is_empty() {
+    if self.rows.is_empty() {
       self.table_state.select(None);
     } else {
       self.table_state.select(Some(0));
@@ -380,10 +1283,82 @@ impl App {
   }
 
   fn jump_end(&mut self) {
-    if self.results.is_empty() {
+    if self.rows.is_empty() {
       self.table_state.select(None);
     } else {
-      self.table_state.select(Some(self.results.len() - 1));
+      self.table_state.select(Some(self.rows.len() - 1));
+    }
+  }
+
+  fn jump_to_row(&mut self, row: usize) {
+    if self.facets.focused {
+      self.facets.select(row);
+    } else if !self.rows.is_empty() {
+      self.table_state.select(Some(row.min(self.rows.len() - 1)));
+    }
+  }
+
+  /// Moves to the next or previous row with highlighted search matches, wrapping around.
+  fn jump_to_match(&mut self, forward: bool) {
+    let len = self.rows.len();
+    let current = self.table_state.selected().unwrap_or(0);
+    let found = (1..=len)
+      .map(|step| {
+        if forward {
+          (current + step) % len
+        } else {
+          (current + len - step % len) % len
+        }
+      })
+      .find(|row| {
+        self
+          .rows
+          .result_index(*row)
+          .and_then(|result_index| self.results.get(result_index))
+          .is_some_and(|result| !result.highlights.is_empty())
+      });
+
+    match found {
+      Some(row) => self.table_state.select(Some(row)),
+      None => self.set_message("no highlighted matches"),
+    }
+  }
+
+  fn set_mode(&mut self, mode: Mode) {
+    if !self.keymaps.modal {
+      self.set_message("modal navigation is off; set modal = true in keymap.toml");
+      return;
+    }
+    self.mode = mode;
+    self.count = 0;
+    self.key_dispatcher.clear();
+  }
+
+  /// Adds a typed digit to the normal-mode count. A leading `0` is left to the keymap.
+  fn push_count_digit(&mut self, key: &KeyEvent) -> bool {
+    if !is_search_input_key(key) {
+      return false;
+    }
+    let crossterm::event::KeyCode::Char(ch) = key.code else {
+      return false;
+    };
+    let Some(digit) = ch.to_digit(10) else {
+      return false;
+    };
+    if digit == 0 && self.count == 0 {
+      return false;
+    }
+
+    self.count = (self.count * 10 + digit as usize).min(MAX_COUNT);
+    true
+  }
+
+  fn mode_label(&self) -> Option<String> {
+    match self.mode {
+      Mode::Browse => None,
+      Mode::Normal if self.count > 0 => Some(format!("NORMAL {}", self.count)),
+      Mode::Normal => Some("NORMAL".to_string()),
+      Mode::Insert => Some("INSERT".to_string()),
     }
   }
 
@@ -411,7 +1386,7 @@ impl App {
 
   fn current_book_index(&self) -> Option<usize> {
     let selected = self.table_state.selected()?;
-    let result = self.results.get(selected)?;
+    let result = self.results.get(self.rows.result_index(selected)?)?;
     Some(result.book_index)
   }
 
@@ -489,6 +1464,9 @@ impl App {
   fn submit_command(&mut self, input: String) -> Result<()> {
     let command = input.trim().trim_start_matches(':').trim().to_string();
     self.command_state.push_history(command.clone());
+    self
+      .history
+      .push(HistoryKind::Command, &command, self.history_limit);
     self.execute_command(&command)
   }
 
@@ -503,7 +1481,13 @@ impl App {
     }
 
     let buffer = prompt.buffer();
-    let completion = command_completion_for(&buffer.input, buffer.cursor);
+    let completion = command_completion_for(
+      &buffer.input,
+      buffer.cursor,
+      &self.catalog,
+      &self.layout,
+      &self.layouts,
+    );
     self
       .command_state
       .set_completion_preserving_selection(completion);
@@ -514,6 +1498,38 @@ impl App {
     let mut parts = command.split_whitespace();
     match parts.next() {
       Some("sort") => self.execute_sort_command(parts.collect()),
+      Some("group") => self.execute_group_command(parts.collect()),
+      Some("display") => self.execute_display_command(parts.collect()),
+      Some("column") => self.execute_column_command(parts.collect()),
+      Some("layout") => self.execute_layout_command(parts.collect()),
+      Some("view") => match parts.collect::<Vec<_>>().join(" ").as_str() {
+        "" => {
+          self.open_picker(PickerTarget::View);
+          Ok(())
+        }
+        name => self.set_view(name),
+      },
+      Some("search") => {
+        let name = parts.collect::<Vec<_>>().join(" ");
+        if name.is_empty() {
+          self.open_picker(PickerTarget::SavedSearch);
+          Ok(())
+        } else {
+          self.apply_saved_search(&name)
+        }
+      }
+      Some("library") => match parts.collect::<Vec<_>>().join(" ").as_str() {
+        "" => {
+          self.open_picker(PickerTarget::Library);
+          Ok(())
+        }
+        "off" | "none" => self.set_library(None),
+        name => self.set_library(Some(name.to_string())),
+      },
+      Some("save-search") => self.save_query(SavedKind::Search, parts.collect()),
+      Some("save-library") => self.save_query(SavedKind::Library, parts.collect()),
+      Some("delete-search") => self.delete_saved(SavedKind::Search, parts.collect()),
+      Some("delete-library") => self.delete_saved(SavedKind::Library, parts.collect()),
       Some("help") if parts.next().is_none() => {
         self.key_help = true;
         Ok(())
@@ -530,8 +1546,15 @@ impl App {
   }
 
   fn execute_sort_command(&mut self, args: Vec<&str>) -> Result<()> {
-    match SortSpec::parse(&args) {
+    match self.sort_spec.updated(&args) {
       Ok(sort_spec) => {
+        if let Some(column) = sort_spec
+          .columns()
+          .find(|column| column.field().is_none() && self.layout.column(column).is_none())
+        {
+          self.set_message(format!("unknown template column: {}", column.name()));
+          return Ok(());
+        }
         let focused = self.current_book_index();
         self.sort_spec = sort_spec;
         self.sort_results(focused);
@@ -542,6 +1565,173 @@ impl App {
     Ok(())
   }
 
+  fn execute_column_command(&mut self, args: Vec<&str>) -> Result<()> {
+    let usage = "usage: column <hide|show|move|width> <field> [position|width]";
+    let (action, field, value) = match args.as_slice() {
+      [action, field] => (*action, *field, None),
+      [action, field, value] => (*action, *field, Some(*value)),
+      _ => {
+        self.set_message(usage);
+        return Ok(());
+      }
+    };
+    let Some(key) = ColumnKey::parse(field) else {
+      self.set_message(format!("unknown column field: {field}"));
+      return Ok(());
+    };
+
+    let previous = self.layout.clone();
+    let name = key.name();
+    let result = match (action, value) {
+      ("hide", None) => self
+        .layout
+        .hide_column(&key)
+        .map(|()| format!("column hidden: {name}")),
+      ("show", None) => self
+        .layout
+        .show_column(&key)
+        .map(|()| format!("column shown: {name}")),
+      ("move", Some(position)) => match position.parse() {
+        Ok(position) => self
+          .layout
+          .move_column(&key, position)
+          .map(|()| format!("column {name} moved to {position}")),
+        Err(_) => Err(anyhow!("invalid column position: {position}")),
+      },
+      ("width", Some(width)) => match width.parse() {
+        Ok(width) => self
+          .layout
+          .set_column_width(&key, width)
+          .map(|()| format!("column {name} width: {width}")),
+        Err(_) => Err(anyhow!("invalid column width: {width}")),
+      },
+      _ => {
+        self.set_message(usage);
+        return Ok(());
+      }
+    };
+
+    match result {
+      Ok(message) => {
+        // Column order is also search match priority, so results may need re-sorting.
+        if !self.layout.same_search(&previous) {
+          self.refresh_search_fields()?;
+        }
+        self.set_message(message);
+      }
+      Err(error) => self.set_message(error.to_string()),
+    }
+    Ok(())
+  }
+
+  fn execute_layout_command(&mut self, args: Vec<&str>) -> Result<()> {
+    match args.as_slice() {
+      ["save"] => {
+        self.layouts.set(&self.view, self.layout.clone());
+        match save_layout(&self.layouts) {
+          Ok(path) => self.set_message(format!("layout saved to {}", path.display())),
+          Err(error) => self.set_message(format!("{error:#}")),
+        }
+      }
+      _ => self.set_message("usage: layout save"),
+    }
+    Ok(())
+  }
+
+  /// Saves the current query under a name given as command arguments.
+  fn save_query(&mut self, kind: SavedKind, args: Vec<&str>) -> Result<()> {
+    let name = args.join(" ");
+    let query = self.input.text().trim().to_string();
+    let label = saved_kind_label(kind);
+    if name.is_empty() {
+      self.set_message(format!("usage: save-{label} <name>"));
+    } else if query.is_empty() {
+      self.set_message(format!("cannot save an empty query as {label} {name}"));
+    } else {
+      self.catalog.save(kind, &name, &query)?;
+      self.set_message(format!("saved {label} {name}: {query}"));
+    }
+    Ok(())
+  }
+
+  fn delete_saved(&mut self, kind: SavedKind, args: Vec<&str>) -> Result<()> {
+    let name = args.join(" ");
+    let label = saved_kind_label(kind);
+    if name.is_empty() {
+      self.set_message(format!("usage: delete-{label} <name>"));
+    } else if self.catalog.delete(kind, &name)? {
+      self.set_message(format!("deleted {label} {name}"));
+    } else if self.catalog.get(kind, &name).is_some() {
+      self.set_message(format!(
+        "{label} {name} comes from Calibre and cannot be deleted here"
+      ));
+    } else {
+      self.set_message(format!("unknown {label}: {name}"));
+    }
+    Ok(())
+  }
+
+  fn execute_group_command(&mut self, args: Vec<&str>) -> Result<()> {
+    let group_field = match args.as_slice() {
+      ["off" | "none"] => None,
+      [field] => match BookField::parse(field) {
+        Some(field) if can_group_by(field) => Some(field),
+        Some(field) => {
+          self.set_message(format!("cannot group by {}", field.name()));
+          return Ok(());
+        }
+        None => {
+          self.set_message(format!("unknown group field: {field}"));
+          return Ok(());
+        }
+      },
+      _ => {
+        self.set_message(format!("usage: group <{}>", GROUP_FIELDS.join("|")));
+        return Ok(());
+      }
+    };
+
+    let focused = self.current_book_index();
+    if group_field != self.group_field {
+      self.collapsed_groups.clear();
+    }
+    self.group_field = group_field;
+    self.rebuild_rows();
+    let row = focused
+      .and_then(|book_index| self.row_of_book(book_index))
+      .or((!self.rows.is_empty()).then_some(0));
+    self.table_state.select(row);
+    match group_field {
+      Some(field) => self.set_message(format!("group: {}", field.name())),
+      None => self.set_message("group: off"),
+    }
+    Ok(())
+  }
+
+  fn execute_display_command(&mut self, args: Vec<&str>) -> Result<()> {
+    let display = match args.as_slice() {
+      [] => {
+        self.set_message(format!("display: {}", self.display.name()));
+        return Ok(());
+      }
+      [mode] => match DisplayMode::parse(mode) {
+        Some(display) => display,
+        None => {
+          self.set_message(format!("unknown display mode: {mode}"));
+          return Ok(());
+        }
+      },
+      _ => {
+        self.set_message("usage: display <table|card|grid>");
+        return Ok(());
+      }
+    };
+
+    self.display = display;
+    self.set_message(format!("display: {}", display.name()));
+    Ok(())
+  }
+
   fn handle_key_help_input(&mut self, event: Event) {
     let Event::Key(key) = event else {
       return;
@@ -555,12 +1745,27 @@ impl App {
   }
 
   fn key_help_entries(&self) -> Vec<KeyHelpEntry> {
-    let context = if self.prompt.is_some() {
+    let context = if self.prompt.is_some() || self.picker.is_some() {
       KeyContext::Input
     } else {
       KeyContext::Browser
     };
-    self.keymap.help_entries(context)
+    mode_bindings(&self.keymaps, self.mode).help_entries(context)
+  }
+
+  /// Modal bindings share the browser context, so the title tells which mode they are for.
+  fn key_help_title(&self) -> &'static str {
+    if self.picker.is_some() {
+      return "Key Bindings: Picker";
+    }
+    if self.prompt.is_some() {
+      return "Key Bindings: Prompt";
+    }
+    match self.mode {
+      Mode::Browse => "Key Bindings",
+      Mode::Normal => "Key Bindings: Normal Mode",
+      Mode::Insert => "Key Bindings: Insert Mode",
+    }
   }
 
   fn set_message(&mut self, message: impl Into<String>) {
@@ -568,6 +1773,44 @@ impl App {
   }
 }
 
+fn saved_kind_label(kind: SavedKind) -> &'static str {
+  match kind {
+    SavedKind::Search => "search",
+    SavedKind::Library => "library",
+  }
+}
+
+fn mode_bindings(keymaps: &Keymaps, mode: Mode) -> &KeyBindings {
+  match mode {
+    Mode::Browse => &keymaps.browser,
+    Mode::Normal => &keymaps.normal,
+    Mode::Insert => &keymaps.insert,
+  }
+}
+
+fn is_repeatable_action(action: &str) -> bool {
+  matches!(
+    action,
+    "move_up"
+      | "move_down"
+      | "move_left"
+      | "move_right"
+      | "page_up"
+      | "page_down"
+      | "next_match"
+      | "previous_match"
+  )
+}
+
+/// Actions that move through the list, after which `Up` / `Down` move it again.
+fn ends_search_editing(action: &str) -> bool {
+  is_repeatable_action(action)
+    || matches!(
+      action,
+      "jump_start" | "jump_end" | "toggle_selection" | "select_all" | "switch_focus"
+    )
+}
+
 fn is_search_input_key(key: &KeyEvent) -> bool {
   key.kind == KeyEventKind::Press
     && matches!(key.code, crossterm::event::KeyCode::Char(_))
@@ -696,7 +1939,13 @@ fn open_with_command(command: &[String], path: &Path) -> Result<()> {
   Ok(())
 }
 
-fn command_completion_for(input: &str, cursor: usize) -> Option<CommandCompletion> {
+fn command_completion_for(
+  input: &str,
+  cursor: usize,
+  catalog: &SearchCatalog,
+  layout: &Layout,
+  layouts: &Layouts,
+) -> Option<CommandCompletion> {
   let cursor = cursor.min(input.len());
   let before_cursor = input.get(..cursor)?;
   let normalized = before_cursor.trim_start_matches(':');
@@ -721,13 +1970,76 @@ fn command_completion_for(input: &str, cursor: usize) -> Option<CommandCompletio
   }
 
   match tokens[0] {
-    "sort" => sort_command_completion(&tokens[1..], ends_with_space, word_start, cursor, prefix),
+    "sort" => sort_command_completion(
+      layout,
+      &tokens[1..],
+      ends_with_space,
+      word_start,
+      cursor,
+      prefix,
+    ),
+    "group" => group_command_completion(&tokens[1..], ends_with_space, word_start, cursor, prefix),
+    "column" => column_command_completion(
+      layout,
+      &tokens[1..],
+      ends_with_space,
+      word_start,
+      cursor,
+      prefix,
+    ),
+    "view" => saved_name_completion(
+      layouts.names(),
+      &tokens[1..],
+      ends_with_space,
+      word_start,
+      cursor,
+      prefix,
+    ),
+    "display" => saved_name_completion(
+      DisplayMode::NAMES.to_vec(),
+      &tokens[1..],
+      ends_with_space,
+      word_start,
+      cursor,
+      prefix,
+    ),
+    "layout" => saved_name_completion(
+      vec!["save"],
+      &tokens[1..],
+      ends_with_space,
+      word_start,
+      cursor,
+      prefix,
+    ),
+    "search" | "delete-search" | "save-search" => saved_name_completion(
+      catalog.names(SavedKind::Search),
+      &tokens[1..],
+      ends_with_space,
+      word_start,
+      cursor,
+      prefix,
+    ),
+    "library" | "delete-library" | "save-library" => {
+      let mut names = catalog.names(SavedKind::Library);
+      if tokens[0] == "library" {
+        names.push("off");
+      }
+      saved_name_completion(
+        names,
+        &tokens[1..],
+        ends_with_space,
+        word_start,
+        cursor,
+        prefix,
+      )
+    }
     "help" => None,
     _ => None,
   }
 }
 
 fn sort_command_completion(
+  layout: &Layout,
   args: &[&str],
   ends_with_space: bool,
   word_start: usize,
@@ -739,7 +2051,11 @@ fn sort_command_completion(
   } else {
     args.get(..args.len().saturating_sub(1))?
   };
-  let candidates = sort_completion_candidates(completed_args)?;
+  let mut candidates = sort_completion_candidates(completed_args)?
+    .into_iter()
+    .map(str::to_string)
+    .collect::<Vec<_>>();
+  candidates.extend(layout.template_names());
   let replace_start = if ends_with_space { cursor } else { word_start };
   let prefix = if ends_with_space { "" } else { prefix };
 
@@ -753,6 +2069,122 @@ fn sort_command_completion(
   )
 }
 
+fn group_command_completion(
+  args: &[&str],
+  ends_with_space: bool,
+  word_start: usize,
+  cursor: usize,
+  prefix: &str,
+) -> Option<CommandCompletion> {
+  let completed_args = if ends_with_space {
+    args.len()
+  } else {
+    args.len().saturating_sub(1)
+  };
+  if completed_args > 0 {
+    return None;
+  }
+  let replace_start = if ends_with_space { cursor } else { word_start };
+  let prefix = if ends_with_space { "" } else { prefix };
+
+  completion_from_candidates(
+    replace_start,
+    cursor,
+    prefix,
+    filter_completion_candidates(GROUP_FIELDS.iter().copied(), prefix),
+    ends_with_space,
+    false,
+  )
+}
+
+/// Completes `column <action> <field> [position]`. Fields are limited to the ones the action
+/// applies to, and positions to the visible column count.
+fn column_command_completion(
+  layout: &Layout,
+  args: &[&str],
+  ends_with_space: bool,
+  word_start: usize,
+  cursor: usize,
+  prefix: &str,
+) -> Option<CommandCompletion> {
+  let completed_args = if ends_with_space {
+    args
+  } else {
+    args.get(..args.len().saturating_sub(1))?
+  };
+  let column = |field: &str| ColumnKey::parse(field).and_then(|key| layout.column(&key));
+  let fields = |include: &dyn Fn(&str) -> bool| {
+    SORT_FIELDS
+      .iter()
+      .map(|field| field.to_string())
+      .chain(layout.template_names())
+      .filter(|field| include(field))
+      .collect::<Vec<_>>()
+  };
+  let shown = |field: &str| column(field).is_some_and(|column| column.visible);
+  let (candidates, append_space) = match completed_args {
+    [] => (
+      COLUMN_ACTIONS
+        .iter()
+        .map(|action| action.to_string())
+        .collect(),
+      true,
+    ),
+    ["hide"] => (fields(&shown), false),
+    ["show"] => (fields(&|field| !shown(field)), false),
+    ["move"] => (fields(&shown), true),
+    ["width"] => (fields(&|field| column(field).is_some()), true),
+    ["move", _] => (
+      (1..=layout.visible_columns().count())
+        .map(|position| position.to_string())
+        .collect::<Vec<_>>(),
+      false,
+    ),
+    _ => return None,
+  };
+  let replace_start = if ends_with_space { cursor } else { word_start };
+  let prefix = if ends_with_space { "" } else { prefix };
+
+  completion_from_candidates(
+    replace_start,
+    cursor,
+    prefix,
+    filter_completion_candidates(candidates, prefix),
+    ends_with_space,
+    append_space,
+  )
+}
+
+/// Completes a saved search or library name.
+fn saved_name_completion(
+  names: Vec<&str>,
+  args: &[&str],
+  ends_with_space: bool,
+  word_start: usize,
+  cursor: usize,
+  prefix: &str,
+) -> Option<CommandCompletion> {
+  let completed_args = if ends_with_space {
+    args.len()
+  } else {
+    args.len().saturating_sub(1)
+  };
+  if completed_args > 0 {
+    return None;
+  }
+  let replace_start = if ends_with_space { cursor } else { word_start };
+  let prefix = if ends_with_space { "" } else { prefix };
+
+  completion_from_candidates(
+    replace_start,
+    cursor,
+    prefix,
+    filter_completion_candidates(names, prefix),
+    ends_with_space,
+    false,
+  )
+}
+
 fn completion_from_candidates(
   replace_start: usize,
   replace_end: usize,
@@ -777,29 +2209,67 @@ fn completion_from_candidates(
 
 fn sort_completion_candidates(completed_args: &[&str]) -> Option<Vec<&'static str>> {
   let mut expecting_field = true;
+  let mut after_field = false;
+  let mut display_applies = false;
   for arg in completed_args {
+    if is_sort_modifier(arg) {
+      expecting_field = true;
+      after_field = false;
+      continue;
+    }
+
     if expecting_field {
-      BookField::parse(arg)?;
+      display_applies = applies_display_sort(ColumnKey::parse(arg)?);
       expecting_field = false;
+      after_field = true;
       continue;
     }
 
-    if is_sort_direction(arg) {
-      expecting_field = true;
-    } else if BookField::parse(arg).is_some() {
-      expecting_field = false;
+    if let Some(column) = ColumnKey::parse(arg) {
+      display_applies = applies_display_sort(column);
+      after_field = true;
+    } else if (after_field && is_sort_direction(arg))
+      || is_sort_option(arg)
+      || (display_applies && arg.eq_ignore_ascii_case(DISPLAY_SORT_OPTION))
+    {
+      after_field = false;
     } else {
       return None;
     }
   }
 
-  if expecting_field {
-    Some(SORT_FIELDS.to_vec())
-  } else {
-    let mut candidates = SORT_DIRECTIONS.to_vec();
-    candidates.extend(SORT_FIELDS);
-    Some(candidates)
+  let mut candidates = Vec::new();
+  if !expecting_field {
+    if after_field {
+      candidates.extend(SORT_DIRECTIONS);
+    }
+    if display_applies {
+      candidates.push(DISPLAY_SORT_OPTION);
+    }
+    candidates.extend(SORT_OPTIONS);
   }
+  candidates.extend(SORT_FIELDS);
+  candidates.extend(SORT_MODIFIERS);
+  Some(candidates)
+}
+
+fn applies_display_sort(column: ColumnKey) -> bool {
+  matches!(
+    column,
+    ColumnKey::Field(BookField::Title | BookField::Authors)
+  )
+}
+
+fn is_sort_modifier(input: &str) -> bool {
+  SORT_MODIFIERS
+    .iter()
+    .any(|modifier| modifier.eq_ignore_ascii_case(input))
+}
+
+fn is_sort_option(input: &str) -> bool {
+  SORT_OPTIONS
+    .iter()
+    .any(|option| option.eq_ignore_ascii_case(input))
 }
 
 fn is_sort_direction(input: &str) -> bool {
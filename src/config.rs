@@ -1,4 +1,6 @@
-use crate::config_file::{CommentedToml, TomlComment, app_config_dir, load_toml_or_reset};
+use crate::config_file::{CommentedToml, TomlComment, app_config_dir, load_toml_or_reset_with};
+use crate::i18n::filter::Translators;
+use crate::sort::SortSpec;
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -15,6 +17,17 @@ pub struct Config {
   pub library_path: PathBuf,
   pub open: OpenConfig,
   pub filter: FilterConfig,
+  pub sort: SortConfig,
+  pub session: SessionConfig,
+  pub history: HistoryConfig,
+  pub display: DisplayConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(default)]
+pub struct SortConfig {
+  pub default: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,21 +42,66 @@ pub struct OpenConfig {
 #[serde(default)]
 pub struct FilterConfig {
   pub translators: Vec<FilterTranslator>,
+  pub auto_fallback: Vec<FilterTranslator>,
   pub pinyin_fuzzy: bool,
   pub pinyin_fuzzy_groups: Vec<Vec<String>>,
+  pub custom_translators: Vec<CustomTranslatorConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomTranslatorConfig {
+  pub name: String,
+  #[serde(default)]
+  pub chars: BTreeMap<String, String>,
+  #[serde(default)]
+  pub rules: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(default)]
+pub struct SessionConfig {
+  pub restore_query: bool,
+  pub restore_sort: bool,
+  pub restore_library: bool,
+  pub restore_view: bool,
+  pub restore_display: bool,
+  pub restore_cursor: bool,
+  pub restore_selection: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(default)]
+pub struct HistoryConfig {
+  pub max_entries: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(default)]
+pub struct DisplayConfig {
+  pub covers: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum FilterTranslator {
+  Auto,
   #[serde(rename = "pinyin")]
   ChinesePinyin,
+  ChineseVariant,
   #[serde(rename = "romaji")]
   JapaneseRomaji,
+  KoreanLatin,
   GermanLatin,
   FrenchLatin,
   SpanishLatin,
   RussianLatin,
+  UkrainianLatin,
+  SerbianLatin,
+  GreekLatin,
 }
 
 impl Default for Config {
@@ -52,6 +110,10 @@ impl Default for Config {
       library_path: find_calibre_library().unwrap_or_default(),
       open: OpenConfig::default(),
       filter: FilterConfig::default(),
+      sort: SortConfig::default(),
+      session: SessionConfig::default(),
+      history: HistoryConfig::default(),
+      display: DisplayConfig::default(),
     }
   }
 }
@@ -68,6 +130,7 @@ impl Default for FilterConfig {
   fn default() -> Self {
     Self {
       translators: vec![FilterTranslator::ChinesePinyin],
+      auto_fallback: Vec::new(),
       pinyin_fuzzy: true,
       pinyin_fuzzy_groups: vec![
         vec!["on".to_string(), "ong".to_string()],
@@ -75,10 +138,46 @@ impl Default for FilterConfig {
         vec!["en".to_string(), "eng".to_string()],
         vec!["in".to_string(), "ing".to_string()],
       ],
+      custom_translators: Vec::new(),
+    }
+  }
+}
+
+impl Default for SortConfig {
+  fn default() -> Self {
+    Self {
+      default: "title asc".to_string(),
+    }
+  }
+}
+
+impl SortConfig {
+  pub fn default_spec(&self) -> Result<SortSpec> {
+    SortSpec::parse(&self.default.split_whitespace().collect::<Vec<_>>())
+      .with_context(|| format!("invalid sort.default '{}'", self.default))
+  }
+}
+
+impl Default for SessionConfig {
+  fn default() -> Self {
+    Self {
+      restore_query: true,
+      restore_sort: true,
+      restore_library: true,
+      restore_view: true,
+      restore_display: true,
+      restore_cursor: true,
+      restore_selection: true,
     }
   }
 }
 
+impl Default for HistoryConfig {
+  fn default() -> Self {
+    Self { max_entries: 1000 }
+  }
+}
+
 impl CommentedToml for Config {
   fn comments() -> &'static [TomlComment] {
     &[
@@ -118,8 +217,18 @@ impl CommentedToml for Config {
       TomlComment {
         path: "filter.translators",
         lines: &[
-          "Search translators to enable.",
-          "Supported values: pinyin, romaji, german-latin, french-latin, spanish-latin, russian-latin.",
+          "Search translators to enable for every book.",
+          "Use auto to pick translators per book from its Calibre languages (zho: pinyin and chinese-variant,",
+          "jpn: romaji, kor: korean-latin, deu: german-latin, ...). Translators listed next to auto still apply to every book.",
+          "Supported values: auto, pinyin, chinese-variant, romaji, korean-latin, german-latin, french-latin, spanish-latin, russian-latin,",
+          "ukrainian-latin, serbian-latin, greek-latin.",
+        ],
+      },
+      TomlComment {
+        path: "filter.auto_fallback",
+        lines: &[
+          "Translators used by auto for books without a language.",
+          "Books with languages that no translator covers use plain search only.",
         ],
       },
       TomlComment {
@@ -133,6 +242,104 @@ impl CommentedToml for Config {
           "The first item in each group is treated as canonical.",
         ],
       },
+      TomlComment {
+        path: "filter.custom_translators",
+        lines: &[
+          "User-defined transliteration tables, each enabled as an extra search translator.",
+          "Example:",
+          "[[filter.custom_translators]]",
+          "name = \"vietnamese\"",
+          "chars = { \"ơ\" = \"o\", \"ư\" = \"u\", \"đ\" = \"d\" }",
+          "rules = { \"ng\" = \"n\" }",
+        ],
+      },
+      TomlComment {
+        path: "filter.custom_translators.name",
+        lines: &["Unique translator name used in error messages."],
+      },
+      TomlComment {
+        path: "filter.custom_translators.chars",
+        lines: &[
+          "Single-character mappings. Keys match case-insensitively.",
+          "Unmapped characters are folded like the built-in Latin translators.",
+        ],
+      },
+      TomlComment {
+        path: "filter.custom_translators.rules",
+        lines: &[
+          "Multi-character rules. They are tried before chars, longest key first,",
+          "and a match highlights every source character it covers.",
+        ],
+      },
+      TomlComment {
+        path: "sort",
+        lines: &["Sorting options."],
+      },
+      TomlComment {
+        path: "sort.default",
+        lines: &[
+          "Sort order used at startup, in sort command syntax.",
+          "Results are grouped by the first matching search field before these keys are applied;",
+          "add nogroup to sort by the keys alone. Example: \"authors asc series asc natural nogroup\"",
+        ],
+      },
+      TomlComment {
+        path: "session",
+        lines: &[
+          "State restored from the previous session. It is saved on quit.",
+          "Run with --fresh to skip restoring once.",
+        ],
+      },
+      TomlComment {
+        path: "session.restore_query",
+        lines: &["Restore the last search query."],
+      },
+      TomlComment {
+        path: "session.restore_sort",
+        lines: &["Restore the last sort order."],
+      },
+      TomlComment {
+        path: "session.restore_library",
+        lines: &["Restore the active virtual library."],
+      },
+      TomlComment {
+        path: "session.restore_view",
+        lines: &["Restore the active layout view."],
+      },
+      TomlComment {
+        path: "session.restore_display",
+        lines: &["Restore the book list display mode: table, card, or grid."],
+      },
+      TomlComment {
+        path: "session.restore_cursor",
+        lines: &["Restore the focused book and scroll position."],
+      },
+      TomlComment {
+        path: "session.restore_selection",
+        lines: &["Restore selected books."],
+      },
+      TomlComment {
+        path: "history",
+        lines: &["Search and command history, saved across sessions."],
+      },
+      TomlComment {
+        path: "history.max_entries",
+        lines: &[
+          "Maximum entries kept for each of search and command history.",
+          "Repeated entries are kept once, at their latest position. Use 0 to turn history off.",
+        ],
+      },
+      TomlComment {
+        path: "display",
+        lines: &["Book list display options."],
+      },
+      TomlComment {
+        path: "display.covers",
+        lines: &[
+          "Show cover thumbnails in grid tiles, from the cover.jpg Calibre keeps for each book.",
+          "Thumbnails use half-block characters and need a terminal with true color.",
+        ],
+      },
     ]
   }
 }
@@ -140,7 +347,8 @@ impl CommentedToml for Config {
 pub fn load_config() -> Result<Config> {
   let config_dir = app_config_dir()?;
   let config_path = config_dir.join("config.toml");
-  let mut config: Config = load_toml_or_reset(&config_path, Config::default(), "main")?;
+  let mut config =
+    load_toml_or_reset_with(&config_path, Config::default(), "main", validate_config)?;
 
   if config.library_path.as_os_str().is_empty() {
     config.library_path = find_calibre_library()
@@ -157,6 +365,12 @@ pub fn load_config() -> Result<Config> {
   Ok(config)
 }
 
+fn validate_config(config: Config) -> Result<Config> {
+  Translators::from_config(&config.filter).context("invalid filter configuration")?;
+  config.sort.default_spec()?;
+  Ok(config)
+}
+
 fn is_calibre_library(path: &Path) -> bool {
   path.join("metadata.db").exists()
 }
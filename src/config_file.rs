@@ -40,6 +40,17 @@ pub fn app_config_dir() -> Result<PathBuf> {
   Ok(config_dir)
 }
 
+/// Directory for state written by the app itself, such as the last session.
+pub fn app_state_dir() -> Result<PathBuf> {
+  let state_dir = dirs::state_dir()
+    .or_else(dirs::data_local_dir)
+    .context("could not get state directory")?
+    .join("calibre-tui");
+  fs::create_dir_all(&state_dir)
+    .with_context(|| format!("failed to create state directory at {:?}", state_dir))?;
+  Ok(state_dir)
+}
+
 pub fn load_toml_or_reset<T>(path: &Path, default: T, label: &str) -> Result<T>
 where
   T: Clone + Serialize + DeserializeOwned + CommentedToml,
@@ -112,7 +123,7 @@ where
   Ok((config, compiled, next_document != content))
 }
 
-fn write_config<T>(path: &Path, config: &T) -> Result<()>
+pub fn write_config<T>(path: &Path, config: &T) -> Result<()>
 where
   T: Serialize + CommentedToml,
 {
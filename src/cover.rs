@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use image::ImageReader;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::Widget;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Cover thumbnails for the grid display, read when a tile first shows them.
+#[derive(Debug, Default)]
+pub struct CoverCache {
+  /// Thumbnails by book index, with the cell size they were scaled to. `None` marks a cover
+  /// that could not be read, so it is not read again on every frame.
+  thumbnails: HashMap<usize, ((u16, u16), Option<Thumbnail>)>,
+}
+
+/// Cover pixels drawn with half blocks, two pixels stacked in each cell.
+#[derive(Debug)]
+pub struct Thumbnail {
+  width: u16,
+  height: u16,
+  pixels: Vec<[u8; 3]>,
+}
+
+impl CoverCache {
+  /// The cover of a book scaled to fit `size` cells, read again when the size changes.
+  pub fn thumbnail(
+    &mut self,
+    book_index: usize,
+    path: &Path,
+    size: (u16, u16),
+  ) -> Option<&Thumbnail> {
+    if self
+      .thumbnails
+      .get(&book_index)
+      .is_none_or(|(cached_size, _)| *cached_size != size)
+    {
+      let thumbnail = Thumbnail::load(path, size).ok();
+      self.thumbnails.insert(book_index, (size, thumbnail));
+    }
+    self
+      .thumbnails
+      .get(&book_index)
+      .and_then(|(_, thumbnail)| thumbnail.as_ref())
+  }
+}
+
+impl Thumbnail {
+  fn load(path: &Path, (width, height): (u16, u16)) -> Result<Self> {
+    let image = ImageReader::open(path)
+      .with_context(|| format!("failed to open cover {}", path.display()))?
+      .decode()
+      .with_context(|| format!("failed to decode cover {}", path.display()))?
+      .thumbnail(u32::from(width), u32::from(height) * 2)
+      .to_rgb8();
+    Ok(Self {
+      width: u16::try_from(image.width()).unwrap_or(width),
+      height: u16::try_from(image.height()).unwrap_or(height * 2),
+      pixels: image.pixels().map(|pixel| pixel.0).collect(),
+    })
+  }
+
+  fn pixel(&self, x: u16, y: u16) -> Option<Color> {
+    if x >= self.width || y >= self.height {
+      return None;
+    }
+    let [r, g, b] = self.pixels[usize::from(y) * usize::from(self.width) + usize::from(x)];
+    Some(Color::Rgb(r, g, b))
+  }
+}
+
+impl Widget for &Thumbnail {
+  /// Draws the thumbnail centered horizontally at the top of `area`.
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    let left = area.x + area.width.saturating_sub(self.width) / 2;
+    for row in 0..self.height.div_ceil(2).min(area.height) {
+      for column in 0..self.width.min(area.width) {
+        let Some(cell) = buf.cell_mut((left + column, area.y + row)) else {
+          continue;
+        };
+        if let Some(top) = self.pixel(column, row * 2) {
+          cell.set_symbol("▀").set_fg(top);
+        }
+        if let Some(bottom) = self.pixel(column, row * 2 + 1) {
+          cell.set_bg(bottom);
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,372 @@
+use crate::filter::{SearchResult, field_filter_term, parse_field_filter, query_tokens};
+use crate::group::group_values;
+use crate::i18n::filter::normalize_plain_query;
+use crate::layout::BookField;
+use crate::utils::book::{Book, tag_parent, tag_paths};
+use ratatui::widgets::ListState;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+
+/// Fields listed in the facet panel, in display order.
+pub const FACET_FIELDS: &[BookField] = &[
+  BookField::Authors,
+  BookField::Series,
+  BookField::Tags,
+  BookField::Formats,
+  BookField::Publisher,
+  BookField::Languages,
+];
+
+#[derive(Debug, Clone)]
+pub struct FacetValue {
+  /// Field value; empty for books without one.
+  pub value: String,
+  /// Number of current results with this value.
+  pub count: usize,
+  /// The query already filters on this value.
+  pub active: bool,
+  /// Nesting level of hierarchical tags; 0 for top-level values.
+  pub depth: usize,
+  pub has_children: bool,
+  pub expanded: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FacetCategory {
+  pub field: BookField,
+  pub values: Vec<FacetValue>,
+  pub expanded: bool,
+}
+
+/// One line of the facet panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FacetRow {
+  /// Header of the category at this index in [`FacetPanel::categories`].
+  Category(usize),
+  /// A value as (category index, value index).
+  Value(usize, usize),
+}
+
+/// Side panel listing field values of the current results with their counts.
+#[derive(Debug, Default)]
+pub struct FacetPanel {
+  pub visible: bool,
+  pub focused: bool,
+  pub categories: Vec<FacetCategory>,
+  pub rows: Vec<FacetRow>,
+  pub list_state: ListState,
+  expanded: BTreeSet<BookField>,
+  /// Expanded parent tags, as (field, value).
+  expanded_values: BTreeSet<(BookField, String)>,
+}
+
+impl FacetPanel {
+  /// Recounts values for new results, keeping the cursor on the same entry when possible.
+  pub fn refresh(&mut self, books: &[Book], results: &[SearchResult], query: &str) {
+    let selected = self.selected_entry();
+    let active = active_filters(query);
+
+    self.categories = FACET_FIELDS
+      .iter()
+      .map(|field| {
+        let mut counts = HashMap::<String, usize>::new();
+        for result in results {
+          let Some(book) = books.get(result.book_index) else {
+            continue;
+          };
+          let values = group_values(book, *field);
+          let mut values = if *field == BookField::Tags {
+            values
+              .iter()
+              .flat_map(|value| tag_paths(value))
+              .map(str::to_string)
+              .collect::<Vec<_>>()
+          } else {
+            values
+          };
+          values.sort_unstable();
+          values.dedup();
+          for value in values {
+            *counts.entry(value).or_default() += 1;
+          }
+        }
+
+        FacetCategory {
+          field: *field,
+          values: facet_tree(*field, counts, &active),
+          expanded: self.expanded.contains(field),
+        }
+      })
+      .collect();
+
+    self.rebuild_rows();
+    let row = selected
+      .and_then(|(field, value)| self.row_of_entry(field, value.as_deref()))
+      .unwrap_or_else(|| {
+        self
+          .list_state
+          .selected()
+          .unwrap_or(0)
+          .min(self.rows.len().saturating_sub(1))
+      });
+    self
+      .list_state
+      .select((!self.rows.is_empty()).then_some(row));
+  }
+
+  /// The field value under the cursor.
+  pub fn selected_value(&self) -> Option<(BookField, &FacetValue)> {
+    match self.rows.get(self.list_state.selected()?)? {
+      FacetRow::Value(category_index, value_index) => {
+        let category = self.categories.get(*category_index)?;
+        Some((category.field, category.values.get(*value_index)?))
+      }
+      FacetRow::Category(_) => None,
+    }
+  }
+
+  /// Expands or collapses the entry under the cursor: a category, a parent tag, or the
+  /// parent of a nested tag. Other values collapse their category.
+  pub fn toggle(&mut self) {
+    let Some(row) = self
+      .list_state
+      .selected()
+      .and_then(|row| self.rows.get(row))
+      .copied()
+    else {
+      return;
+    };
+    let (category_index, value_index) = match row {
+      FacetRow::Category(category_index) => (category_index, None),
+      FacetRow::Value(category_index, value_index) => (category_index, Some(value_index)),
+    };
+    let Some(category) = self.categories.get_mut(category_index) else {
+      return;
+    };
+
+    let value = value_index.and_then(|value_index| category.values.get(value_index));
+    let node = match value {
+      Some(value) if value.has_children => Some(value.value.clone()),
+      Some(value) if value.depth > 0 => tag_parent(&value.value).map(str::to_string),
+      _ => None,
+    };
+    let target = match node {
+      Some(node) => {
+        let key = (category.field, node.clone());
+        if !self.expanded_values.remove(&key) {
+          self.expanded_values.insert(key);
+        }
+        Some(node)
+      }
+      None => {
+        category.expanded = !category.expanded;
+        if category.expanded {
+          self.expanded.insert(category.field);
+        } else {
+          self.expanded.remove(&category.field);
+        }
+        None
+      }
+    };
+
+    let field = category.field;
+    self.rebuild_rows();
+    let row = self
+      .row_of_entry(field, target.as_deref())
+      .unwrap_or_default();
+    self.select(row);
+  }
+
+  pub fn previous(&mut self) {
+    let index = match self.list_state.selected() {
+      Some(0) | None => self.rows.len().saturating_sub(1),
+      Some(index) => index - 1,
+    };
+    self.select(index);
+  }
+
+  pub fn next(&mut self) {
+    let index = match self.list_state.selected() {
+      Some(index) if index + 1 < self.rows.len() => index + 1,
+      _ => 0,
+    };
+    self.select(index);
+  }
+
+  pub fn page_up(&mut self, page_size: usize) {
+    let index = self
+      .list_state
+      .selected()
+      .unwrap_or(0)
+      .saturating_sub(page_size);
+    self.select(index);
+  }
+
+  pub fn page_down(&mut self, page_size: usize) {
+    let index = self
+      .list_state
+      .selected()
+      .unwrap_or(0)
+      .saturating_add(page_size);
+    self.select(index);
+  }
+
+  pub fn jump_start(&mut self) {
+    self.select(0);
+  }
+
+  pub fn jump_end(&mut self) {
+    self.select(self.rows.len().saturating_sub(1));
+  }
+
+  pub fn select(&mut self, index: usize) {
+    if self.rows.is_empty() {
+      self.list_state.select(None);
+    } else {
+      self.list_state.select(Some(index.min(self.rows.len() - 1)));
+    }
+  }
+
+  fn rebuild_rows(&mut self) {
+    for category in &mut self.categories {
+      for value in &mut category.values {
+        value.expanded = value.has_children
+          && self
+            .expanded_values
+            .contains(&(category.field, value.value.clone()));
+      }
+    }
+
+    self.rows.clear();
+    for (category_index, category) in self.categories.iter().enumerate() {
+      self.rows.push(FacetRow::Category(category_index));
+      if !category.expanded {
+        continue;
+      }
+
+      // Values are in tree order, so children of a collapsed tag follow it directly.
+      let mut collapsed_depth = None;
+      for (value_index, value) in category.values.iter().enumerate() {
+        if collapsed_depth.is_some_and(|depth| value.depth > depth) {
+          continue;
+        }
+        collapsed_depth = None;
+        self.rows.push(FacetRow::Value(category_index, value_index));
+        if value.has_children && !value.expanded {
+          collapsed_depth = Some(value.depth);
+        }
+      }
+    }
+  }
+
+  /// The entry under the cursor as (field, value), with no value for a category header.
+  fn selected_entry(&self) -> Option<(BookField, Option<String>)> {
+    match self.rows.get(self.list_state.selected()?)? {
+      FacetRow::Category(category_index) => {
+        Some((self.categories.get(*category_index)?.field, None))
+      }
+      FacetRow::Value(..) => self
+        .selected_value()
+        .map(|(field, value)| (field, Some(value.value.clone()))),
+    }
+  }
+
+  fn row_of_entry(&self, field: BookField, value: Option<&str>) -> Option<usize> {
+    self.rows.iter().position(|row| match *row {
+      FacetRow::Category(category_index) => {
+        value.is_none() && self.categories[category_index].field == field
+      }
+      FacetRow::Value(category_index, value_index) => {
+        let category = &self.categories[category_index];
+        category.field == field && Some(category.values[value_index].value.as_str()) == value
+      }
+    })
+  }
+}
+
+/// Adds an exact filter for a field value to the query, or removes it when present.
+pub fn toggle_field_filter(query: &str, field: BookField, value: &str) -> String {
+  let normalized = normalize_plain_query(value);
+  let matching = query_tokens(query)
+    .into_iter()
+    .filter(|token| {
+      parse_field_filter(&token.text).is_some_and(|filter| {
+        filter.exact && filter.field == field && normalize_plain_query(&filter.value) == normalized
+      })
+    })
+    .collect::<Vec<_>>();
+
+  if matching.is_empty() {
+    let query = query.trim_end();
+    let term = field_filter_term(field, value);
+    return if query.is_empty() {
+      term
+    } else {
+      format!("{query} {term}")
+    };
+  }
+
+  let mut query = query.to_string();
+  for token in matching.iter().rev() {
+    query.replace_range(token.range.clone(), "");
+  }
+  query.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Orders values by count, with children of hierarchical tags directly below their parent.
+fn facet_tree(
+  field: BookField,
+  counts: HashMap<String, usize>,
+  active: &BTreeSet<(BookField, String)>,
+) -> Vec<FacetValue> {
+  let mut children = HashMap::<Option<String>, Vec<(String, usize)>>::new();
+  for (value, count) in counts {
+    let parent = match field {
+      BookField::Tags => tag_parent(&value).map(str::to_string),
+      _ => None,
+    };
+    children.entry(parent).or_default().push((value, count));
+  }
+  for siblings in children.values_mut() {
+    siblings.sort_by(compare_facet_values);
+  }
+
+  let mut values = Vec::new();
+  let mut stack = children
+    .remove(&None)
+    .unwrap_or_default()
+    .into_iter()
+    .rev()
+    .map(|entry| (entry, 0))
+    .collect::<Vec<_>>();
+  while let Some(((value, count), depth)) = stack.pop() {
+    let nested = children.remove(&Some(value.clone())).unwrap_or_default();
+    values.push(FacetValue {
+      active: active.contains(&(field, normalize_plain_query(&value))),
+      has_children: !nested.is_empty(),
+      expanded: false,
+      value,
+      count,
+      depth,
+    });
+    stack.extend(nested.into_iter().rev().map(|entry| (entry, depth + 1)));
+  }
+  values
+}
+
+fn compare_facet_values(left: &(String, usize), right: &(String, usize)) -> Ordering {
+  right
+    .1
+    .cmp(&left.1)
+    .then_with(|| left.0.is_empty().cmp(&right.0.is_empty()))
+    .then_with(|| left.0.to_lowercase().cmp(&right.0.to_lowercase()))
+}
+
+fn active_filters(query: &str) -> BTreeSet<(BookField, String)> {
+  query_tokens(query)
+    .iter()
+    .filter_map(|token| parse_field_filter(&token.text))
+    .filter(|filter| filter.exact)
+    .map(|filter| (filter.field, normalize_plain_query(&filter.value)))
+    .collect()
+}
@@ -1,11 +1,18 @@
 use crate::config::FilterConfig;
-use crate::i18n::filter::{IndexedText, Translators, index_plain_text, normalize_plain_query};
-use crate::layout::{BookField, Layout};
-use crate::utils::book::Book;
+use crate::i18n::filter::{
+  IndexedText, TranslatedText, TranslatorSelection, Translators, index_plain_text,
+  normalize_plain_query,
+};
+use crate::layout::{BookField, ColumnKey, Layout};
+use crate::utils::book::{Book, TAG_SEPARATOR};
 use anyhow::{Result, anyhow};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
 
 pub type HighlightRanges = Vec<(usize, usize)>;
 
+static NO_RANGES: HighlightRanges = Vec::new();
+
 #[derive(Debug, Clone, Default)]
 pub struct BookHighlights {
   pub title: HighlightRanges,
@@ -13,6 +20,10 @@ pub struct BookHighlights {
   pub series: HighlightRanges,
   pub formats: HighlightRanges,
   pub tags: HighlightRanges,
+  pub publisher: HighlightRanges,
+  pub languages: HighlightRanges,
+  /// Ranges in searchable template columns, by column name.
+  pub templates: BTreeMap<String, HighlightRanges>,
 }
 
 #[derive(Debug, Clone)]
@@ -24,11 +35,34 @@ pub struct SearchResult {
 pub struct BookSearch {
   books: Vec<IndexedBook>,
   translators: Translators,
-  search_fields: Vec<BookField>,
+  search_fields: Vec<ColumnKey>,
+  /// Source of each indexed template column, to re-index only changed templates.
+  template_sources: HashMap<String, String>,
 }
 
 struct QueryTerm {
-  versions: Vec<String>,
+  /// Restricts the term to one field, for `field:value` filters.
+  field: Option<BookField>,
+  /// Match one whole field value, for `field:=value` filters.
+  exact: bool,
+  plain: String,
+  translated: Vec<String>,
+}
+
+/// One query term and its byte range in the query. Double quotes group words into one
+/// term and are removed from `text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryToken {
+  pub text: String,
+  pub range: Range<usize>,
+}
+
+/// A `field:value` or `field:=value` query term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldFilter {
+  pub field: BookField,
+  pub exact: bool,
+  pub value: String,
 }
 
 #[derive(Debug, Clone)]
@@ -39,37 +73,95 @@ struct IndexedBook {
   series: IndexedField,
   formats: IndexedField,
   tags: IndexedField,
+  publisher: IndexedField,
+  languages: IndexedField,
+  templates: HashMap<String, IndexedField>,
 }
 
 #[derive(Debug, Clone)]
 struct IndexedField {
-  versions: Vec<IndexedText>,
+  plain: IndexedText,
+  translated: Vec<TranslatedText>,
+  /// Normalized non-empty values with their highlight range, for exact filters.
+  values: Vec<(String, (usize, usize))>,
 }
 
 impl BookSearch {
   pub fn new(books: &[Book], config: &FilterConfig, layout: &Layout) -> Result<Self> {
     let translators = Translators::from_config(config)?;
-    let search_fields = layout.search_fields().collect::<Vec<_>>();
-    let books = books
+    let indexed_books = books
       .iter()
       .enumerate()
       .map(|(book_index, book)| {
+        let selection = translators.select(&book.languages);
         Ok(IndexedBook {
           book_index,
-          title: index_field(&book.title, &translators)?,
-          authors: index_field(&book.authors.join(" & "), &translators)?,
-          series: index_field(&book.series, &translators)?,
-          formats: index_field(&book.formats.join(", "), &translators)?,
-          tags: index_field(&book.tags.join(", "), &translators)?,
+          title: index_field(
+            std::slice::from_ref(&book.title),
+            "",
+            &translators,
+            &selection,
+          )?,
+          authors: index_field(&book.authors, " & ", &translators, &selection)?,
+          series: index_field(
+            std::slice::from_ref(&book.series),
+            "",
+            &translators,
+            &selection,
+          )?,
+          formats: index_field(&book.formats, ", ", &translators, &selection)?,
+          tags: index_field(&book.tags, ", ", &translators, &selection)?,
+          publisher: index_field(
+            std::slice::from_ref(&book.publisher),
+            "",
+            &translators,
+            &selection,
+          )?,
+          languages: index_field(&book.languages, ", ", &translators, &selection)?,
+          templates: HashMap::new(),
         })
       })
       .collect::<Result<Vec<_>>>()?;
 
-    Ok(Self {
-      books,
+    let mut search = Self {
+      books: indexed_books,
       translators,
-      search_fields,
-    })
+      search_fields: Vec::new(),
+      template_sources: HashMap::new(),
+    };
+    search.set_search_fields(books, layout)?;
+    Ok(search)
+  }
+
+  /// Uses the searchable columns of a changed layout. Book fields are always indexed;
+  /// template columns are indexed when they become searchable or their template changes.
+  pub fn set_search_fields(&mut self, books: &[Book], layout: &Layout) -> Result<()> {
+    for (name, template) in layout.search_templates() {
+      if self
+        .template_sources
+        .get(name)
+        .is_some_and(|source| source == template.source())
+      {
+        continue;
+      }
+      for indexed in &mut self.books {
+        let book = &books[indexed.book_index];
+        let selection = self.translators.select(&book.languages);
+        let text = template.render(book);
+        let field = index_field(
+          std::slice::from_ref(&text),
+          "",
+          &self.translators,
+          &selection,
+        )?;
+        indexed.templates.insert(name.to_string(), field);
+      }
+      self
+        .template_sources
+        .insert(name.to_string(), template.source().to_string());
+    }
+    self.search_fields = layout.search_fields().cloned().collect();
+    Ok(())
   }
 
   pub fn search(&self, query: &str) -> Result<Vec<SearchResult>> {
@@ -94,7 +186,11 @@ impl BookSearch {
       let mut all_terms_matched = true;
 
       for term in &terms {
-        match match_book_term(book, &self.search_fields, term)? {
+        let term_highlights = match term.field {
+          Some(field) => match_field_filter(book, field, term)?,
+          None => match_book_term(book, &self.search_fields, term)?,
+        };
+        match term_highlights {
           Some(term_highlights) => highlights.extend(term_highlights),
           None => {
             all_terms_matched = false;
@@ -105,31 +201,103 @@ impl BookSearch {
 
       if all_terms_matched {
         highlights.normalize();
-        if !highlights.is_empty() {
-          results.push(SearchResult {
-            book_index: book.book_index,
-            highlights,
-          });
-        }
+        results.push(SearchResult {
+          book_index: book.book_index,
+          highlights,
+        });
       }
     }
 
     Ok(results)
   }
 
+  /// Parses query terms. A filter without a value, such as `tags:` while typing, is ignored
+  /// except for `field:=`, which matches books without a value.
   fn query_terms(&self, query: &str) -> Result<Vec<QueryTerm>> {
-    query
-      .split_whitespace()
-      .map(|term| {
-        let mut versions = vec![normalize_plain_query(term)];
-        versions.extend(self.translators.normalize_queries(term)?);
-        Ok(QueryTerm { versions })
-      })
-      .collect()
+    let mut terms = Vec::new();
+    for token in query_tokens(query) {
+      let (field, exact, text) = match parse_field_filter(&token.text) {
+        Some(filter) => (Some(filter.field), filter.exact, filter.value),
+        None => (None, false, token.text),
+      };
+      let plain = normalize_plain_query(&text);
+      if plain.is_empty() && !exact {
+        continue;
+      }
+      terms.push(QueryTerm {
+        field,
+        exact,
+        plain,
+        translated: if exact {
+          Vec::new()
+        } else {
+          self.translators.normalize_queries(&text)?
+        },
+      });
+    }
+    Ok(terms)
+  }
+}
+
+/// Splits a query into terms at whitespace outside double quotes.
+pub fn query_tokens(query: &str) -> Vec<QueryToken> {
+  let mut tokens = Vec::new();
+  let mut current: Option<QueryToken> = None;
+  let mut quoted = false;
+
+  for (index, ch) in query.char_indices() {
+    if ch.is_whitespace() && !quoted {
+      tokens.extend(current.take());
+      continue;
+    }
+
+    let token = current.get_or_insert_with(|| QueryToken {
+      text: String::new(),
+      range: index..index,
+    });
+    token.range.end = index + ch.len_utf8();
+    if ch == '"' {
+      quoted = !quoted;
+    } else {
+      token.text.push(ch);
+    }
+  }
+  tokens.extend(current);
+
+  tokens
+}
+
+pub fn parse_field_filter(token: &str) -> Option<FieldFilter> {
+  let (name, value) = token.split_once(':')?;
+  let field = BookField::parse(name)?;
+  let (exact, value) = match value.strip_prefix('=') {
+    Some(value) => (true, value),
+    None => (false, value),
+  };
+  Some(FieldFilter {
+    field,
+    exact,
+    value: value.to_string(),
+  })
+}
+
+/// Query term that matches books having exactly this field value.
+pub fn field_filter_term(field: BookField, value: &str) -> String {
+  if value.is_empty() || value.contains(char::is_whitespace) {
+    format!("{}:=\"{}\"", field.name(), value.replace('"', ""))
+  } else {
+    format!("{}:={}", field.name(), value.replace('"', ""))
   }
 }
 
 impl BookHighlights {
+  pub fn column_ranges(&self, column: &ColumnKey) -> &HighlightRanges {
+    match column {
+      ColumnKey::Field(field) => self.ranges(*field),
+      ColumnKey::Template(name) => self.templates.get(name).unwrap_or(&NO_RANGES),
+    }
+  }
+
   pub fn ranges(&self, field: BookField) -> &HighlightRanges {
     match field {
       BookField::Title => &self.title,
@@ -137,15 +305,20 @@ impl BookHighlights {
       BookField::Series => &self.series,
       BookField::Formats => &self.formats,
       BookField::Tags => &self.tags,
+      BookField::Publisher => &self.publisher,
+      BookField::Languages => &self.languages,
     }
   }
 
-  fn is_empty(&self) -> bool {
+  pub fn is_empty(&self) -> bool {
     self.title.is_empty()
       && self.authors.is_empty()
       && self.series.is_empty()
       && self.formats.is_empty()
       && self.tags.is_empty()
+      && self.publisher.is_empty()
+      && self.languages.is_empty()
+      && self.templates.values().all(Vec::is_empty)
   }
 
   fn extend(&mut self, other: Self) {
@@ -154,6 +327,11 @@ impl BookHighlights {
     self.series.extend(other.series);
     self.formats.extend(other.formats);
     self.tags.extend(other.tags);
+    self.publisher.extend(other.publisher);
+    self.languages.extend(other.languages);
+    for (name, ranges) in other.templates {
+      self.templates.entry(name).or_default().extend(ranges);
+    }
   }
 
   fn normalize(&mut self) {
@@ -162,6 +340,20 @@ impl BookHighlights {
     normalize_ranges(&mut self.series);
     normalize_ranges(&mut self.formats);
     normalize_ranges(&mut self.tags);
+    normalize_ranges(&mut self.publisher);
+    normalize_ranges(&mut self.languages);
+    self.templates.values_mut().for_each(normalize_ranges);
+  }
+
+  fn extend_column(&mut self, column: &ColumnKey, ranges: HighlightRanges) {
+    match column {
+      ColumnKey::Field(field) => self.extend_field(*field, ranges),
+      ColumnKey::Template(name) => self
+        .templates
+        .entry(name.clone())
+        .or_default()
+        .extend(ranges),
+    }
   }
 
   fn extend_field(&mut self, field: BookField, ranges: HighlightRanges) {
@@ -171,37 +363,115 @@ impl BookHighlights {
       BookField::Series => self.series.extend(ranges),
       BookField::Formats => self.formats.extend(ranges),
       BookField::Tags => self.tags.extend(ranges),
+      BookField::Publisher => self.publisher.extend(ranges),
+      BookField::Languages => self.languages.extend(ranges),
+    }
+  }
+}
+
+fn index_field(
+  values: &[String],
+  separator: &str,
+  translators: &Translators,
+  selection: &TranslatorSelection,
+) -> Result<IndexedField> {
+  let text = values.join(separator);
+  let separator_len = non_whitespace_len(separator);
+  let mut indexed_values = Vec::new();
+  let mut start = 0;
+  for value in values {
+    let end = start + non_whitespace_len(value);
+    let normalized = normalize_plain_query(value);
+    if !normalized.is_empty() {
+      indexed_values.push((normalized, (start, end)));
     }
+    start = end + separator_len;
   }
+
+  Ok(IndexedField {
+    plain: index_plain_text(&text),
+    translated: translators.index_texts(&text, selection)?,
+    values: indexed_values,
+  })
 }
 
-fn index_field(text: &str, translators: &Translators) -> Result<IndexedField> {
-  let mut versions = vec![index_plain_text(text)];
-  versions.extend(translators.index_texts(text)?);
-  Ok(IndexedField { versions })
+/// Highlight ranges count non-whitespace chars of the displayed text.
+fn non_whitespace_len(text: &str) -> usize {
+  text.chars().filter(|ch| !ch.is_whitespace()).count()
 }
 
-fn match_field(field: &IndexedField, queries: &[String]) -> Result<Option<HighlightRanges>> {
-  for (version, query) in field.versions.iter().zip(queries) {
-    if let Some(range) = match_text(version, query)? {
-      return Ok(Some(vec![range]));
+fn match_field(field: &IndexedField, term: &QueryTerm) -> Result<Option<HighlightRanges>> {
+  if let Some(range) = match_text(&field.plain, &term.plain)? {
+    return Ok(Some(vec![range]));
+  }
+
+  for version in &field.translated {
+    for translator_index in &version.translators {
+      let Some(query) = term.translated.get(*translator_index) else {
+        continue;
+      };
+      if let Some(range) = match_text(&version.indexed, query)? {
+        return Ok(Some(vec![range]));
+      }
     }
   }
 
   Ok(None)
 }
 
+/// Matches one whole field value. With `hierarchical`, a parent tag also matches its
+/// children, so `fiction` matches `fiction.sciencefiction`.
+fn match_value(field: &IndexedField, value: &str, hierarchical: bool) -> Option<HighlightRanges> {
+  if value.is_empty() {
+    return field.values.is_empty().then(Vec::new);
+  }
+  let ranges = field
+    .values
+    .iter()
+    .filter(|(indexed, _)| {
+      indexed == value
+        || hierarchical
+          && indexed
+            .strip_prefix(value)
+            .is_some_and(|rest| rest.starts_with(TAG_SEPARATOR))
+    })
+    .map(|(_, range)| *range)
+    .collect::<Vec<_>>();
+  (!ranges.is_empty()).then_some(ranges)
+}
+
+fn match_field_filter(
+  book: &IndexedBook,
+  field: BookField,
+  term: &QueryTerm,
+) -> Result<Option<BookHighlights>> {
+  let indexed = book.field(field);
+  let ranges = if term.exact {
+    match_value(indexed, &term.plain, field == BookField::Tags)
+  } else {
+    match_field(indexed, term)?
+  };
+
+  Ok(ranges.map(|ranges| {
+    let mut highlights = BookHighlights::default();
+    highlights.extend_field(field, ranges);
+    highlights
+  }))
+}
+
 fn match_book_term(
   book: &IndexedBook,
-  search_fields: &[BookField],
+  search_fields: &[ColumnKey],
   term: &QueryTerm,
 ) -> Result<Option<BookHighlights>> {
   let mut highlights = BookHighlights::default();
-  for field in search_fields {
-    highlights.extend_field(
-      *field,
-      match_field(book.field(*field), &term.versions)?.unwrap_or_default(),
-    );
+  for column in search_fields {
+    let Some(indexed) = book.column(column) else {
+      continue;
+    };
+    if let Some(ranges) = match_field(indexed, term)? {
+      highlights.extend_column(column, ranges);
+    }
   }
 
   if highlights.is_empty() {
@@ -213,6 +483,13 @@ fn match_book_term(
 }
 
 impl IndexedBook {
+  fn column(&self, column: &ColumnKey) -> Option<&IndexedField> {
+    match column {
+      ColumnKey::Field(field) => Some(self.field(*field)),
+      ColumnKey::Template(name) => self.templates.get(name),
+    }
+  }
+
   fn field(&self, field: BookField) -> &IndexedField {
     match field {
       BookField::Title => &self.title,
@@ -220,6 +497,8 @@ impl IndexedBook {
       BookField::Series => &self.series,
       BookField::Formats => &self.formats,
       BookField::Tags => &self.tags,
+      BookField::Publisher => &self.publisher,
+      BookField::Languages => &self.languages,
     }
   }
 }
@@ -280,6 +559,16 @@ fn match_text(text: &IndexedText, query: &str) -> Result<Option<(usize, usize)>>
 
   let start_token = char_to_token[start_char];
   let end_token = char_to_token[end_char - 1] + 1;
+  let (Some(&start_source), Some(&end_source)) = (
+    text.source_bounds.get(start_token),
+    text.source_bounds.get(end_token),
+  ) else {
+    return Err(anyhow!(
+      "search index source map has {} bounds but token {} was requested",
+      text.source_bounds.len(),
+      end_token
+    ));
+  };
 
-  Ok(Some((start_token, end_token)))
+  Ok(Some((start_source, end_source)))
 }
@@ -0,0 +1,140 @@
+use crate::filter::SearchResult;
+use crate::layout::BookField;
+use crate::utils::book::Book;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// One row of the book table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableRow {
+  /// Header of the group at this index in [`TableRows::groups`].
+  Group(usize),
+  /// The search result at this index.
+  Result(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct ResultGroup {
+  /// Group value; empty for books without one.
+  pub value: String,
+  pub len: usize,
+  pub collapsed: bool,
+}
+
+/// Table rows for the current results, either flat or split into groups.
+#[derive(Debug, Clone, Default)]
+pub struct TableRows {
+  pub rows: Vec<TableRow>,
+  pub groups: Vec<ResultGroup>,
+  pub group_field: Option<BookField>,
+}
+
+impl TableRows {
+  pub fn flat(results: &[SearchResult]) -> Self {
+    Self {
+      rows: (0..results.len()).map(TableRow::Result).collect(),
+      groups: Vec::new(),
+      group_field: None,
+    }
+  }
+
+  /// Groups results by a field value. Books with several values appear in each group,
+  /// and results keep their sorted order inside a group.
+  pub fn grouped(
+    results: &[SearchResult],
+    books: &[Book],
+    field: BookField,
+    collapsed: &BTreeSet<String>,
+  ) -> Self {
+    let mut members = BTreeMap::<(bool, String, String), Vec<usize>>::new();
+    for (result_index, result) in results.iter().enumerate() {
+      let Some(book) = books.get(result.book_index) else {
+        continue;
+      };
+      let mut values = group_values(book, field);
+      values.sort_unstable();
+      values.dedup();
+      for value in values {
+        let key = (value.is_empty(), value.to_lowercase(), value);
+        members.entry(key).or_default().push(result_index);
+      }
+    }
+
+    let mut rows = Vec::new();
+    let mut groups = Vec::new();
+    for ((_, _, value), result_indices) in members {
+      let is_collapsed = collapsed.contains(&value);
+      rows.push(TableRow::Group(groups.len()));
+      if !is_collapsed {
+        rows.extend(result_indices.iter().copied().map(TableRow::Result));
+      }
+      groups.push(ResultGroup {
+        value,
+        len: result_indices.len(),
+        collapsed: is_collapsed,
+      });
+    }
+
+    Self {
+      rows,
+      groups,
+      group_field: Some(field),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.rows.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.rows.is_empty()
+  }
+
+  pub fn result_index(&self, row: usize) -> Option<usize> {
+    match self.rows.get(row)? {
+      TableRow::Result(result_index) => Some(*result_index),
+      TableRow::Group(_) => None,
+    }
+  }
+
+  /// The group a row belongs to: the header itself, or the nearest header above a result.
+  pub fn group_at(&self, row: usize) -> Option<&ResultGroup> {
+    self
+      .rows
+      .get(..=row)?
+      .iter()
+      .rev()
+      .find_map(|row| match row {
+        TableRow::Group(group_index) => self.groups.get(*group_index),
+        TableRow::Result(_) => None,
+      })
+  }
+
+  pub fn header_row(&self, value: &str) -> Option<usize> {
+    self.rows.iter().position(|row| {
+      matches!(row, TableRow::Group(group_index)
+        if self.groups.get(*group_index).is_some_and(|group| group.value == value))
+    })
+  }
+}
+
+pub fn can_group_by(field: BookField) -> bool {
+  !matches!(field, BookField::Title)
+}
+
+/// Values a book contributes to a field, or one empty value when it has none.
+pub fn group_values(book: &Book, field: BookField) -> Vec<String> {
+  let values = match field {
+    BookField::Title => vec![book.title.clone()],
+    BookField::Authors => book.authors.clone(),
+    BookField::Series => vec![book.series.clone()],
+    BookField::Formats => book.formats.clone(),
+    BookField::Tags => book.tags.clone(),
+    BookField::Publisher => vec![book.publisher.clone()],
+    BookField::Languages => book.languages.clone(),
+  };
+  if values.is_empty() {
+    vec![String::new()]
+  } else {
+    values
+  }
+}
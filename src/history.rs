@@ -0,0 +1,145 @@
+use crate::config_file::app_state_dir;
+use crate::picker::{Picker, PickerItem};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Search queries and `:` commands saved across sessions, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct History {
+  pub search: Vec<String>,
+  pub command: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryKind {
+  Search,
+  Command,
+}
+
+impl History {
+  pub fn entries(&self, kind: HistoryKind) -> &[String] {
+    match kind {
+      HistoryKind::Search => &self.search,
+      HistoryKind::Command => &self.command,
+    }
+  }
+
+  /// Appends an entry, moving an identical older entry to the end and dropping the oldest
+  /// entries beyond `limit`.
+  pub fn push(&mut self, kind: HistoryKind, entry: &str, limit: usize) {
+    let entry = entry.trim();
+    if entry.is_empty() {
+      return;
+    }
+    let entries = match kind {
+      HistoryKind::Search => &mut self.search,
+      HistoryKind::Command => &mut self.command,
+    };
+    entries.retain(|existing| existing != entry);
+    entries.push(entry.to_string());
+    truncate_oldest(entries, limit);
+  }
+
+  pub fn truncate(&mut self, limit: usize) {
+    truncate_oldest(&mut self.search, limit);
+    truncate_oldest(&mut self.command, limit);
+  }
+}
+
+/// Up/Down recall position in the search history.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryCursor {
+  /// Index of the recalled entry; `None` while editing a new query.
+  index: Option<usize>,
+  /// Query typed before the first recall, restored after the newest entry.
+  draft: String,
+}
+
+impl HistoryCursor {
+  /// The entry before the recalled one. Editing a recalled entry starts over from the newest.
+  pub fn previous(&mut self, entries: &[String], current: &str) -> Option<String> {
+    let index = match self.recalled(entries, current) {
+      Some(0) => return None,
+      Some(index) => index - 1,
+      None => {
+        self.draft = current.to_string();
+        entries.len().checked_sub(1)?
+      }
+    };
+    self.index = Some(index);
+    Some(entries[index].clone())
+  }
+
+  /// The entry after the recalled one, or the draft after the newest entry.
+  pub fn next(&mut self, entries: &[String], current: &str) -> Option<String> {
+    let index = self.recalled(entries, current)?;
+    if index + 1 < entries.len() {
+      self.index = Some(index + 1);
+      Some(entries[index + 1].clone())
+    } else {
+      self.index = None;
+      Some(std::mem::take(&mut self.draft))
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.index = None;
+  }
+
+  fn recalled(&self, entries: &[String], current: &str) -> Option<usize> {
+    self
+      .index
+      .filter(|index| entries.get(*index).is_some_and(|entry| entry == current))
+  }
+}
+
+/// Picker over one history list, newest first.
+pub fn history_picker(history: &History, kind: HistoryKind) -> Picker {
+  let title = match kind {
+    HistoryKind::Search => "Search History",
+    HistoryKind::Command => "Command History",
+  };
+  let items = history
+    .entries(kind)
+    .iter()
+    .rev()
+    .map(|entry| PickerItem {
+      label: entry.clone(),
+      detail: String::new(),
+      value: entry.clone(),
+    })
+    .collect();
+  Picker::new(title, items)
+}
+
+pub fn load_history() -> Result<History> {
+  let path = history_path()?;
+  if !path.exists() {
+    return Ok(History::default());
+  }
+
+  let content = fs::read_to_string(&path)
+    .with_context(|| format!("failed to read history file: {:?}", path))?;
+  toml::from_str(&content)
+    .with_context(|| format!("failed to parse history file '{}'", path.display()))
+}
+
+pub fn save_history(history: &History) -> Result<()> {
+  let path = history_path()?;
+  fs::write(&path, toml::to_string_pretty(history)?)
+    .with_context(|| format!("failed to write history file to {:?}", path))?;
+  Ok(())
+}
+
+fn truncate_oldest(entries: &mut Vec<String>, limit: usize) {
+  if entries.len() > limit {
+    entries.drain(..entries.len() - limit);
+  }
+}
+
+fn history_path() -> Result<PathBuf> {
+  Ok(app_state_dir()?.join("history.toml"))
+}
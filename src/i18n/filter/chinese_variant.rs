@@ -0,0 +1,54 @@
+use super::{IndexedText, Translator};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static TRADITIONAL_TO_SIMPLIFIED: LazyLock<HashMap<char, char>> =
+  LazyLock::new(|| parse_character_table(include_str!("data/ts_characters.txt")));
+
+pub(super) struct ChineseVariantTranslator;
+
+impl Translator for ChineseVariantTranslator {
+  fn index_text(&self, text: &str) -> Result<IndexedText> {
+    Ok(index_chinese_variant_text(text))
+  }
+
+  fn normalize_query(&self, query: &str) -> Result<String> {
+    Ok(index_chinese_variant_text(query).text)
+  }
+}
+
+fn index_chinese_variant_text(text: &str) -> IndexedText {
+  let mut indexed = String::new();
+  let mut token_bounds = vec![0];
+
+  for ch in text.chars().filter(|ch| !ch.is_whitespace()) {
+    indexed.extend(simplified_char(ch).to_lowercase());
+    token_bounds.push(indexed.chars().count());
+  }
+
+  IndexedText::per_char(indexed, token_bounds)
+}
+
+fn simplified_char(ch: char) -> char {
+  TRADITIONAL_TO_SIMPLIFIED.get(&ch).copied().unwrap_or(ch)
+}
+
+fn parse_character_table(table: &str) -> HashMap<char, char> {
+  table
+    .lines()
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .filter_map(|line| {
+      let (traditional, simplified) = line.split_once('\t')?;
+      let traditional = single_char(traditional)?;
+      let simplified = single_char(simplified.split_whitespace().next()?)?;
+      Some((traditional, simplified))
+    })
+    .collect()
+}
+
+fn single_char(text: &str) -> Option<char> {
+  let mut chars = text.chars();
+  let ch = chars.next()?;
+  chars.next().is_none().then_some(ch)
+}
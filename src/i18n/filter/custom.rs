@@ -0,0 +1,129 @@
+use super::{IndexedText, Translator, latin_char};
+use crate::config::CustomTranslatorConfig;
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+
+pub(super) struct CustomTranslator {
+  chars: HashMap<char, String>,
+  rules: Vec<(Vec<char>, String)>,
+}
+
+impl CustomTranslator {
+  pub(super) fn compile(config: &CustomTranslatorConfig) -> Result<Self> {
+    if config.name.trim().is_empty() {
+      bail!("custom translator name must not be empty");
+    }
+
+    let mut chars = HashMap::new();
+    for (from, to) in &config.chars {
+      let mut from_chars = from.chars();
+      let (Some(ch), None) = (from_chars.next(), from_chars.next()) else {
+        bail!(
+          "custom translator '{}': chars key '{}' must be exactly one character",
+          config.name,
+          from
+        );
+      };
+      if ch.is_whitespace() {
+        bail!(
+          "custom translator '{}': chars key must not be whitespace",
+          config.name
+        );
+      }
+      chars.insert(fold_case(ch), search_text(to));
+    }
+
+    let mut rules = Vec::new();
+    for (from, to) in &config.rules {
+      let pattern = from.chars().map(fold_case).collect::<Vec<_>>();
+      if pattern.len() < 2 {
+        bail!(
+          "custom translator '{}': rules key '{}' must have at least two characters; use chars for single characters",
+          config.name,
+          from
+        );
+      }
+      if pattern.iter().any(|ch| ch.is_whitespace()) {
+        bail!(
+          "custom translator '{}': rules key '{}' must not contain whitespace",
+          config.name,
+          from
+        );
+      }
+      rules.push((pattern, search_text(to)));
+    }
+    rules.sort_by_key(|(pattern, _)| std::cmp::Reverse(pattern.len()));
+
+    Ok(Self { chars, rules })
+  }
+
+  fn index(&self, text: &str) -> IndexedText {
+    let chars = text
+      .chars()
+      .filter(|ch| !ch.is_whitespace())
+      .collect::<Vec<_>>();
+    let mut indexed = String::new();
+    let mut token_bounds = vec![0];
+    let mut source_bounds = vec![0];
+    let mut index = 0;
+
+    while index < chars.len() {
+      if let Some((length, replacement)) = self.rule_at(&chars, index) {
+        indexed.push_str(replacement);
+        index += length;
+      } else {
+        let ch = chars[index];
+        match self.chars.get(&fold_case(ch)) {
+          Some(replacement) => indexed.push_str(replacement),
+          None => indexed.push_str(&search_text(&latin_char(ch))),
+        }
+        index += 1;
+      }
+      token_bounds.push(indexed.chars().count());
+      source_bounds.push(index);
+    }
+
+    IndexedText {
+      text: indexed,
+      token_bounds,
+      source_bounds,
+    }
+  }
+
+  fn rule_at(&self, chars: &[char], index: usize) -> Option<(usize, &str)> {
+    self.rules.iter().find_map(|(pattern, replacement)| {
+      let candidate = chars.get(index..index + pattern.len())?;
+      candidate
+        .iter()
+        .zip(pattern)
+        .all(|(ch, expected)| fold_case(*ch) == *expected)
+        .then_some((pattern.len(), replacement.as_str()))
+    })
+  }
+}
+
+impl Translator for CustomTranslator {
+  fn index_text(&self, text: &str) -> Result<IndexedText> {
+    Ok(self.index(text))
+  }
+
+  fn normalize_query(&self, query: &str) -> Result<String> {
+    Ok(self.index(query).text)
+  }
+}
+
+fn fold_case(ch: char) -> char {
+  let mut lower = ch.to_lowercase();
+  match (lower.next(), lower.next()) {
+    (Some(lower), None) => lower,
+    _ => ch,
+  }
+}
+
+fn search_text(text: &str) -> String {
+  text
+    .chars()
+    .filter(|ch| !ch.is_whitespace())
+    .flat_map(char::to_lowercase)
+    .collect()
+}
@@ -1,18 +1,25 @@
-use super::{IndexedText, Translator, index_by_char, latin_char};
+use super::{IndexedText, Translator, Transliteration};
 use anyhow::Result;
 
 pub(super) struct FrenchLatinTranslator;
 
 impl Translator for FrenchLatinTranslator {
   fn index_text(&self, text: &str) -> Result<IndexedText> {
-    Ok(index_by_char(text, french_char))
+    Ok(FRENCH.index(text))
   }
 
   fn normalize_query(&self, query: &str) -> Result<String> {
-    Ok(index_by_char(query, french_char).text)
+    Ok(FRENCH.index(query).text)
+  }
+
+  fn transliteration(&self) -> Option<Transliteration> {
+    Some(FRENCH)
   }
 }
 
-fn french_char(ch: char) -> String {
-  latin_char(ch)
+const FRENCH: Transliteration = Transliteration::ByChar(french_char);
+
+// Accented letters are covered by the shared Latin folding.
+fn french_char(_: char) -> Option<&'static str> {
+  None
 }
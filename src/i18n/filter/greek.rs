@@ -0,0 +1,86 @@
+use super::{IndexedText, Translator, Transliteration};
+use anyhow::Result;
+
+pub(super) struct GreekLatinTranslator;
+
+impl Translator for GreekLatinTranslator {
+  fn index_text(&self, text: &str) -> Result<IndexedText> {
+    Ok(GREEK.index(text))
+  }
+
+  fn normalize_query(&self, query: &str) -> Result<String> {
+    Ok(GREEK.index(query).text)
+  }
+
+  fn transliteration(&self) -> Option<Transliteration> {
+    Some(GREEK)
+  }
+}
+
+const GREEK: Transliteration = Transliteration::ByContext(greek_char);
+
+// ELOT 743 (ISO 843) transliteration.
+fn greek_char(chars: &[char], index: usize) -> Option<&'static str> {
+  let ch = chars[index];
+  let previous = index.checked_sub(1).map(|previous| lower(chars[previous]));
+  let next = chars.get(index + 1).copied().map(lower);
+  Some(match lower(ch) {
+    'α' | 'ά' => "a",
+    'β' => "v",
+    'γ' if matches!(next, Some('γ' | 'ξ' | 'χ')) => "n",
+    'γ' => "g",
+    'δ' => "d",
+    'ε' | 'έ' => "e",
+    'ζ' => "z",
+    'η' | 'ή' => "i",
+    'θ' => "th",
+    'ι' | 'ί' | 'ϊ' | 'ΐ' => "i",
+    'κ' => "k",
+    'λ' => "l",
+    'μ' if next == Some('π') && is_word_edge(chars, index, 2) => "b",
+    'μ' => "m",
+    'ν' => "n",
+    'ξ' => "x",
+    'ο' | 'ό' => "o",
+    'π' if previous == Some('μ') && is_word_edge(chars, index - 1, 2) => "",
+    'π' => "p",
+    'ρ' => "r",
+    'σ' | 'ς' => "s",
+    'τ' => "t",
+    'υ' | 'ύ' if previous == Some('ο') => "u",
+    'υ' | 'ύ' if matches!(previous, Some('α' | 'ε' | 'η')) => {
+      if next.is_none_or(is_voiceless_or_boundary) {
+        "f"
+      } else {
+        "v"
+      }
+    }
+    'υ' | 'ύ' | 'ϋ' | 'ΰ' => "y",
+    'φ' => "f",
+    'χ' => "ch",
+    'ψ' => "ps",
+    'ω' | 'ώ' => "o",
+    _ => return None,
+  })
+}
+
+fn lower(ch: char) -> char {
+  ch.to_lowercase().next().unwrap_or(ch)
+}
+
+fn is_voiceless_or_boundary(ch: char) -> bool {
+  !ch.is_alphabetic()
+    || matches!(
+      ch,
+      'θ' | 'κ' | 'ξ' | 'π' | 'σ' | 'ς' | 'τ' | 'φ' | 'χ' | 'ψ'
+    )
+}
+
+/// Whether the `length` chars starting at `start` begin or end a word.
+fn is_word_edge(chars: &[char], start: usize, length: usize) -> bool {
+  let at_start = start == 0 || !chars[start - 1].is_alphabetic();
+  let at_end = chars
+    .get(start + length)
+    .is_none_or(|next| !next.is_alphabetic());
+  at_start || at_end
+}
@@ -2,9 +2,19 @@ use super::{
   IndexedText, Translator, ascii_search_text, fullwidth_ascii, latin_char, translated_search_text,
 };
 use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static KANJI_READINGS: LazyLock<KanjiReadings> =
+  LazyLock::new(|| parse_kanji_readings(include_str!("data/kanji_readings.txt")));
 
 pub(super) struct JapaneseRomajiTranslator;
 
+struct KanjiReadings {
+  words: HashMap<&'static str, &'static str>,
+  max_length: usize,
+}
+
 impl Translator for JapaneseRomajiTranslator {
   fn index_text(&self, text: &str) -> Result<IndexedText> {
     Ok(index_japanese_text(text))
@@ -16,16 +26,83 @@ impl Translator for JapaneseRomajiTranslator {
 }
 
 fn index_japanese_text(text: &str) -> IndexedText {
-  let chars = text
+  let source = text
     .chars()
     .filter(|ch| !ch.is_whitespace())
     .collect::<Vec<_>>();
+  let mut chars = Vec::new();
+  let mut char_tokens = Vec::new();
+  let mut source_bounds = vec![0];
+  let mut index = 0;
+
+  while index < source.len() {
+    let token = source_bounds.len() - 1;
+    if let Some((length, reading)) = kanji_word_at(&source, index) {
+      for ch in reading.chars() {
+        chars.push(ch);
+        char_tokens.push(token);
+      }
+      index += length;
+    } else {
+      chars.push(source[index]);
+      char_tokens.push(token);
+      index += 1;
+    }
+    source_bounds.push(index);
+  }
+
+  let mut tokens = vec![String::new(); source_bounds.len() - 1];
+  for (part, token) in romaji_parts(&chars).into_iter().zip(char_tokens) {
+    tokens[token].push_str(&part);
+  }
+
+  let mut indexed = String::new();
+  let mut token_bounds = vec![0];
+  for token in tokens {
+    indexed.push_str(&token);
+    token_bounds.push(indexed.chars().count());
+  }
+
+  IndexedText {
+    text: indexed,
+    token_bounds,
+    source_bounds,
+  }
+}
+
+/// Katakana reading of a text, with kanji words read from the embedded word list.
+pub(super) fn kana_reading(text: &str) -> String {
+  let chars = text.chars().collect::<Vec<_>>();
+  let mut reading = String::new();
+  let mut index = 0;
+
+  while index < chars.len() {
+    if let Some((length, word)) = kanji_word_at(&chars, index) {
+      reading.push_str(word);
+      index += length;
+    } else {
+      reading.push(katakana(chars[index]));
+      index += 1;
+    }
+  }
+
+  reading
+}
+
+fn katakana(ch: char) -> char {
+  match ch {
+    'ぁ'..='ゖ' => char::from_u32(ch as u32 + 0x60).unwrap_or(ch),
+    _ => ch,
+  }
+}
+
+fn romaji_parts(chars: &[char]) -> Vec<String> {
   let mut parts = vec![String::new(); chars.len()];
   let mut previous_vowel = None;
 
   for (index, &ch) in chars.iter().enumerate() {
     if is_sokuon(ch) {
-      if let Some(next) = japanese_unit_romaji(&chars, index + 1)
+      if let Some(next) = japanese_unit_romaji(chars, index + 1)
         && let Some(consonant) = first_consonant(next.as_str())
       {
         parts[index].push(consonant);
@@ -62,17 +139,45 @@ fn index_japanese_text(text: &str) -> IndexedText {
     previous_vowel = last_vowel(&parts[index]).or(previous_vowel);
   }
 
-  let mut indexed = String::new();
-  let mut token_bounds = vec![0];
-  for part in parts {
-    indexed.push_str(&part);
-    token_bounds.push(indexed.chars().count());
-  }
+  parts
+}
 
-  IndexedText {
-    text: indexed,
-    token_bounds,
+fn kanji_word_at(chars: &[char], index: usize) -> Option<(usize, &'static str)> {
+  if !chars.get(index).copied().is_some_and(is_kanji) {
+    return None;
   }
+
+  // Collect the longest candidate once and probe its prefixes, longest first.
+  let max_length = KANJI_READINGS.max_length.min(chars.len() - index);
+  let candidate = chars[index..index + max_length].iter().collect::<String>();
+  let mut end = candidate.len();
+  (1..=max_length).rev().find_map(|length| {
+    let word = &candidate[..end];
+    end -= chars[index + length - 1].len_utf8();
+    KANJI_READINGS
+      .words
+      .get(word)
+      .map(|reading| (length, *reading))
+  })
+}
+
+fn is_kanji(ch: char) -> bool {
+  matches!(ch, '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{f900}'..='\u{faff}' | '々')
+}
+
+fn parse_kanji_readings(table: &'static str) -> KanjiReadings {
+  let words = table
+    .lines()
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .filter_map(|line| line.split_once('\t'))
+    .collect::<HashMap<_, _>>();
+  let max_length = words
+    .keys()
+    .map(|word| word.chars().count())
+    .max()
+    .unwrap_or(0);
+
+  KanjiReadings { words, max_length }
 }
 
 fn japanese_char(ch: char) -> String {
@@ -153,6 +258,11 @@ fn japanese_char(ch: char) -> String {
     'ぺ' | 'ペ' => "pe",
     'ぽ' | 'ポ' => "po",
     'ゔ' | 'ヴ' => "vu",
+    'ゎ' | 'ヮ' => "wa",
+    'ゐ' | 'ヰ' => "i",
+    'ゑ' | 'ヱ' => "e",
+    'ゕ' | 'ヵ' => "ka",
+    'ゖ' | 'ヶ' => "ke",
     _ => return latin_char(ch),
   }
   .to_string()
@@ -0,0 +1,293 @@
+use super::{IndexedText, Translator, ascii_search_text, fullwidth_ascii, latin_char};
+use anyhow::Result;
+
+const SYLLABLE_BASE: u32 = 0xac00;
+const SYLLABLE_LAST: u32 = 0xd7a3;
+const MEDIAL_COUNT: u32 = 21;
+const FINAL_COUNT: u32 = 28;
+
+const CHOSEONG_JAMO: [char; 19] = [
+  'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ',
+  'ㅌ', 'ㅍ', 'ㅎ',
+];
+
+const INITIAL_ROMAN: [&str; 19] = [
+  "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+  "h",
+];
+
+const MEDIAL_ROMAN: [&str; 21] = [
+  "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+  "wi", "yu", "eu", "ui", "i",
+];
+
+// Final consonant indices, in Unicode order.
+const FINAL_NONE: u32 = 0;
+const FINAL_G: u32 = 1;
+const FINAL_N: u32 = 4;
+const FINAL_D: u32 = 7;
+const FINAL_L: u32 = 8;
+const FINAL_M: u32 = 16;
+const FINAL_B: u32 = 17;
+const FINAL_NG: u32 = 21;
+
+// Initial consonant indices, in Unicode order.
+const INITIAL_N: u32 = 2;
+const INITIAL_R: u32 = 5;
+const INITIAL_M: u32 = 6;
+const INITIAL_SILENT: u32 = 11;
+
+pub(super) struct KoreanLatinTranslator;
+
+pub(super) struct KoreanChoseongTranslator;
+
+#[derive(Debug, Clone, Copy)]
+struct Syllable {
+  initial: u32,
+  medial: u32,
+  final_consonant: u32,
+}
+
+impl Translator for KoreanLatinTranslator {
+  fn index_text(&self, text: &str) -> Result<IndexedText> {
+    // Fold the joined text like the query, so spellings across syllables fold the same way.
+    let parts = romanized_parts(text);
+    let (indexed, folded_bounds) = fold_romanization(&parts.concat());
+    let mut token_bounds = vec![0];
+    let mut part_end = 0;
+    for part in &parts {
+      part_end += part.chars().count();
+      token_bounds.push(folded_bounds[part_end]);
+    }
+
+    Ok(IndexedText::per_char(indexed, token_bounds))
+  }
+
+  fn normalize_query(&self, query: &str) -> Result<String> {
+    Ok(fold_romanization(&romanized_parts(query).concat()).0)
+  }
+}
+
+impl Translator for KoreanChoseongTranslator {
+  fn index_text(&self, text: &str) -> Result<IndexedText> {
+    let mut indexed = String::new();
+    let mut token_bounds = vec![0];
+
+    for ch in text.chars().filter(|ch| !ch.is_whitespace()) {
+      match decompose(ch) {
+        Some(syllable) => indexed.push(CHOSEONG_JAMO[syllable.initial as usize]),
+        None => indexed.extend(ch.to_lowercase()),
+      }
+      token_bounds.push(indexed.chars().count());
+    }
+
+    Ok(IndexedText::per_char(indexed, token_bounds))
+  }
+
+  fn normalize_query(&self, query: &str) -> Result<String> {
+    let query = query
+      .chars()
+      .filter(|ch| !ch.is_whitespace())
+      .collect::<String>();
+    if query.chars().all(|ch| CHOSEONG_JAMO.contains(&ch)) {
+      Ok(query)
+    } else {
+      Ok(String::new())
+    }
+  }
+}
+
+fn decompose(ch: char) -> Option<Syllable> {
+  let code = ch as u32;
+  if !(SYLLABLE_BASE..=SYLLABLE_LAST).contains(&code) {
+    return None;
+  }
+
+  let offset = code - SYLLABLE_BASE;
+  Some(Syllable {
+    initial: offset / (MEDIAL_COUNT * FINAL_COUNT),
+    medial: (offset % (MEDIAL_COUNT * FINAL_COUNT)) / FINAL_COUNT,
+    final_consonant: offset % FINAL_COUNT,
+  })
+}
+
+fn romanized_parts(text: &str) -> Vec<String> {
+  let chars = text.chars().collect::<Vec<_>>();
+  let mut parts = Vec::new();
+
+  for (index, &ch) in chars.iter().enumerate() {
+    if ch.is_whitespace() {
+      continue;
+    }
+
+    let Some(syllable) = decompose(ch) else {
+      parts.push(non_hangul_search_text(ch));
+      continue;
+    };
+
+    let previous = index
+      .checked_sub(1)
+      .and_then(|previous| chars.get(previous))
+      .and_then(|previous| decompose(*previous));
+    let next = chars.get(index + 1).and_then(|next| decompose(*next));
+
+    let mut part = String::new();
+    part.push_str(initial_roman(syllable, previous));
+    part.push_str(MEDIAL_ROMAN[syllable.medial as usize]);
+    part.push_str(final_roman(syllable, next));
+    parts.push(part);
+  }
+
+  parts
+}
+
+fn initial_roman(syllable: Syllable, previous: Option<Syllable>) -> &'static str {
+  let previous_final = previous.map_or(FINAL_NONE, |previous| previous.final_consonant);
+  if syllable.initial == INITIAL_R {
+    return match final_sound(previous_final) {
+      FINAL_L | FINAL_N => "l",
+      FINAL_NONE => "r",
+      _ => "n",
+    };
+  }
+
+  if syllable.initial == INITIAL_N && final_sound(previous_final) == FINAL_L {
+    return "l";
+  }
+
+  INITIAL_ROMAN[syllable.initial as usize]
+}
+
+fn final_roman(syllable: Syllable, next: Option<Syllable>) -> &'static str {
+  let next_initial = next.map(|next| next.initial);
+  if next_initial == Some(INITIAL_SILENT) {
+    return linked_final_roman(syllable.final_consonant);
+  }
+
+  let sound = final_sound(syllable.final_consonant);
+  match next_initial {
+    Some(INITIAL_N | INITIAL_M) => match sound {
+      FINAL_G => return "ng",
+      FINAL_B => return "m",
+      FINAL_D => return "n",
+      _ => {}
+    },
+    Some(INITIAL_R) => match sound {
+      FINAL_G => return "ng",
+      FINAL_B => return "m",
+      FINAL_N | FINAL_L => return "l",
+      _ => {}
+    },
+    _ => {}
+  }
+
+  match sound {
+    FINAL_G => "k",
+    FINAL_N => "n",
+    FINAL_D => "t",
+    FINAL_L => "l",
+    FINAL_M => "m",
+    FINAL_B => "p",
+    FINAL_NG => "ng",
+    _ => "",
+  }
+}
+
+/// Collapses a final consonant to one of its seven representative sounds.
+fn final_sound(final_consonant: u32) -> u32 {
+  match final_consonant {
+    1..=3 | 9 | 24 => FINAL_G,
+    4..=6 => FINAL_N,
+    7 | 19..=20 | 22..=23 | 25 | 27 => FINAL_D,
+    8 | 11..=13 | 15 => FINAL_L,
+    10 | 16 => FINAL_M,
+    14 | 17..=18 | 26 => FINAL_B,
+    21 => FINAL_NG,
+    _ => FINAL_NONE,
+  }
+}
+
+fn linked_final_roman(final_consonant: u32) -> &'static str {
+  match final_consonant {
+    1 => "g",
+    2 => "kk",
+    3 => "gs",
+    4 => "n",
+    5 => "nj",
+    6 => "n",
+    7 => "d",
+    8 => "r",
+    9 => "lg",
+    10 => "lm",
+    11 => "lb",
+    12 => "ls",
+    13 => "lt",
+    14 => "lp",
+    15 => "r",
+    16 => "m",
+    17 => "b",
+    18 => "bs",
+    19 => "s",
+    20 => "ss",
+    21 => "ng",
+    22 => "j",
+    23 => "ch",
+    24 => "k",
+    25 => "t",
+    26 => "p",
+    _ => "",
+  }
+}
+
+fn non_hangul_search_text(ch: char) -> String {
+  if let Some(converted) = fullwidth_ascii(ch) {
+    return converted.to_ascii_lowercase().to_string();
+  }
+
+  match ch {
+    'ŏ' | 'Ŏ' => "eo".to_string(),
+    'ŭ' | 'Ŭ' => "eu".to_string(),
+    _ => ascii_search_text(&latin_char(ch)),
+  }
+}
+
+/// Folds Revised Romanization and McCune-Reischauer spellings to one key:
+/// unaspirated stops lose their voicing, `r` becomes `l`, and `eo`/`eu` lose the `e`.
+/// Also returns the folded length in chars before each source char and after the last, so
+/// callers can map source positions into the folded text.
+fn fold_romanization(text: &str) -> (String, Vec<usize>) {
+  let chars = text.chars().collect::<Vec<_>>();
+  let mut folded = String::new();
+  let mut folded_len = 0;
+  let mut bounds = Vec::with_capacity(chars.len() + 1);
+  let mut index = 0;
+
+  while index < chars.len() {
+    let rest = &chars[index..];
+    let (replacement, consumed) = match rest {
+      ['s', 'h', 'i', ..] => ("si", 3),
+      ['e', 'o', ..] => ("o", 2),
+      ['e', 'u', ..] => ("u", 2),
+      ['g', ..] => ("k", 1),
+      ['d', ..] => ("t", 1),
+      ['b', ..] => ("p", 1),
+      ['j', ..] => ("ch", 1),
+      ['r', ..] => ("l", 1),
+      _ => {
+        bounds.push(folded_len);
+        folded.push(chars[index]);
+        folded_len += 1;
+        index += 1;
+        continue;
+      }
+    };
+    // Chars inside a multi-char spelling map to where its replacement starts.
+    bounds.extend(std::iter::repeat_n(folded_len, consumed));
+    folded.push_str(replacement);
+    folded_len += replacement.chars().count();
+    index += consumed;
+  }
+  bounds.push(folded_len);
+
+  (folded, bounds)
+}
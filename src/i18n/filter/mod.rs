@@ -1,66 +1,270 @@
 use crate::config::{FilterConfig, FilterTranslator};
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeSet;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::{compose, decompose_compatible};
 
+mod chinese_variant;
+mod custom;
 mod french;
 mod german;
+mod greek;
 mod japanese;
+mod korean;
 mod pinyin;
 mod russian;
+mod serbian;
 mod spanish;
+mod ukrainian;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IndexedText {
   pub text: String,
   pub token_bounds: Vec<usize>,
+  pub source_bounds: Vec<usize>,
+}
+
+impl IndexedText {
+  /// Builds an index where every token covers exactly one non-whitespace source char.
+  pub fn per_char(text: String, token_bounds: Vec<usize>) -> Self {
+    let source_bounds = (0..token_bounds.len()).collect();
+    Self {
+      text,
+      token_bounds,
+      source_bounds,
+    }
+  }
+}
+
+/// One distinct translated version of a text and the translators that produced it.
+#[derive(Debug, Clone)]
+pub struct TranslatedText {
+  pub indexed: IndexedText,
+  pub translators: Vec<usize>,
+}
+
+/// Pronunciation used to order text instead of its code points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortReading {
+  Pinyin,
+  Kana,
 }
 
 pub trait Translator {
   fn index_text(&self, text: &str) -> Result<IndexedText>;
   fn normalize_query(&self, query: &str) -> Result<String>;
+
+  /// Letter mapping of a transliterating translator. `Translators::index_texts` runs every
+  /// selected mapping in one pass over the text.
+  fn transliteration(&self) -> Option<Transliteration> {
+    None
+  }
+}
+
+/// Maps letters to Latin spellings. `None` leaves a char to `latin_char`, which is then
+/// folded once per char for every mapping that leaves it.
+#[derive(Debug, Clone, Copy)]
+pub enum Transliteration {
+  ByChar(fn(char) -> Option<&'static str>),
+  /// Also sees the neighbouring chars, for spellings that depend on position.
+  ByContext(fn(&[char], usize) -> Option<&'static str>),
+}
+
+impl Transliteration {
+  fn apply(self, chars: &[char], index: usize) -> Option<&'static str> {
+    match self {
+      Self::ByChar(transliterate) => transliterate(chars[index]),
+      Self::ByContext(transliterate) => transliterate(chars, index),
+    }
+  }
+
+  fn index(self, text: &str) -> IndexedText {
+    let mut indexed = index_transliterated(text, &[self]);
+    indexed
+      .pop()
+      .unwrap_or_else(|| IndexedText::per_char(String::new(), vec![0]))
+  }
 }
 
 pub struct Translators {
   translators: Vec<Box<dyn Translator>>,
+  scopes: Vec<TranslatorScope>,
+  auto_fallback: Vec<FilterTranslator>,
+}
+
+/// Decides which books a translator indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TranslatorScope {
+  /// Listed explicitly in `filter.translators`, or a custom translator: every book.
+  Always,
+  /// Added by `auto`: only books whose languages select this translator.
+  Auto(FilterTranslator),
 }
 
+/// Indices of the translators that apply to one book.
+pub type TranslatorSelection = Vec<usize>;
+
 impl Translators {
   pub fn from_config(config: &FilterConfig) -> Result<Self> {
-    let mut translators: Vec<Box<dyn Translator>> = Vec::new();
+    if config.auto_fallback.contains(&FilterTranslator::Auto) {
+      bail!("auto_fallback cannot contain 'auto'");
+    }
+
+    let mut translators = Self {
+      translators: Vec::new(),
+      scopes: Vec::new(),
+      auto_fallback: config.auto_fallback.clone(),
+    };
 
     for translator in &config.translators {
-      match translator {
-        FilterTranslator::ChinesePinyin => {
-          translators.push(Box::new(pinyin::ChinesePinyinTranslator::new(config)));
-        }
-        FilterTranslator::JapaneseRomaji => {
-          translators.push(Box::new(japanese::JapaneseRomajiTranslator));
-        }
-        FilterTranslator::GermanLatin => {
-          translators.push(Box::new(german::GermanLatinTranslator));
-        }
-        FilterTranslator::FrenchLatin => {
-          translators.push(Box::new(french::FrenchLatinTranslator));
-        }
-        FilterTranslator::SpanishLatin => {
-          translators.push(Box::new(spanish::SpanishLatinTranslator));
-        }
-        FilterTranslator::RussianLatin => {
-          translators.push(Box::new(russian::RussianLatinTranslator));
+      if *translator != FilterTranslator::Auto {
+        translators.push_builtin(*translator, TranslatorScope::Always, config);
+      }
+    }
+
+    if config.translators.contains(&FilterTranslator::Auto) {
+      for translator in AUTO_TRANSLATORS
+        .iter()
+        .flat_map(|(_, translators)| translators.iter())
+        .chain(&config.auto_fallback)
+      {
+        let scope = TranslatorScope::Auto(*translator);
+        if !config.translators.contains(translator) && !translators.scopes.contains(&scope) {
+          translators.push_builtin(*translator, scope, config);
         }
       }
     }
 
-    Ok(Self { translators })
+    let mut custom_names = BTreeSet::new();
+    for custom in &config.custom_translators {
+      if !custom_names.insert(custom.name.as_str()) {
+        bail!("duplicate custom translator '{}'", custom.name);
+      }
+      translators.push(
+        Box::new(
+          custom::CustomTranslator::compile(custom)
+            .with_context(|| format!("invalid custom translator '{}'", custom.name))?,
+        ),
+        TranslatorScope::Always,
+      );
+    }
+
+    Ok(translators)
+  }
+
+  fn push_builtin(
+    &mut self,
+    translator: FilterTranslator,
+    scope: TranslatorScope,
+    config: &FilterConfig,
+  ) {
+    match translator {
+      FilterTranslator::Auto => {}
+      FilterTranslator::ChinesePinyin => {
+        self.push(
+          Box::new(pinyin::ChinesePinyinTranslator::new(config)),
+          scope,
+        );
+      }
+      FilterTranslator::ChineseVariant => {
+        self.push(Box::new(chinese_variant::ChineseVariantTranslator), scope);
+      }
+      FilterTranslator::JapaneseRomaji => {
+        self.push(Box::new(japanese::JapaneseRomajiTranslator), scope);
+      }
+      FilterTranslator::KoreanLatin => {
+        self.push(Box::new(korean::KoreanLatinTranslator), scope);
+        self.push(Box::new(korean::KoreanChoseongTranslator), scope);
+      }
+      FilterTranslator::GermanLatin => {
+        self.push(Box::new(german::GermanLatinTranslator), scope);
+      }
+      FilterTranslator::FrenchLatin => {
+        self.push(Box::new(french::FrenchLatinTranslator), scope);
+      }
+      FilterTranslator::SpanishLatin => {
+        self.push(Box::new(spanish::SpanishLatinTranslator), scope);
+      }
+      FilterTranslator::RussianLatin => {
+        self.push(Box::new(russian::RussianLatinTranslator), scope);
+      }
+      FilterTranslator::UkrainianLatin => {
+        self.push(Box::new(ukrainian::UkrainianLatinTranslator), scope);
+      }
+      FilterTranslator::SerbianLatin => {
+        self.push(Box::new(serbian::SerbianLatinTranslator), scope);
+      }
+      FilterTranslator::GreekLatin => {
+        self.push(Box::new(greek::GreekLatinTranslator), scope);
+      }
+    }
+  }
+
+  fn push(&mut self, translator: Box<dyn Translator>, scope: TranslatorScope) {
+    self.translators.push(translator);
+    self.scopes.push(scope);
   }
 
-  pub fn index_texts(&self, text: &str) -> Result<Vec<IndexedText>> {
+  /// Chooses translators for a book from its Calibre language codes. Books without a
+  /// language use `auto_fallback`.
+  pub fn select(&self, languages: &[String]) -> TranslatorSelection {
+    let selected: Vec<FilterTranslator> = if languages.is_empty() {
+      self.auto_fallback.clone()
+    } else {
+      languages
+        .iter()
+        .flat_map(|language| language_translators(language))
+        .copied()
+        .collect()
+    };
+
     self
-      .translators
+      .scopes
       .iter()
-      .map(|translator| translator.index_text(text))
+      .enumerate()
+      .filter(|(_, scope)| match scope {
+        TranslatorScope::Always => true,
+        TranslatorScope::Auto(translator) => selected.contains(translator),
+      })
+      .map(|(index, _)| index)
       .collect()
   }
 
+  pub fn index_texts(
+    &self,
+    text: &str,
+    selection: &TranslatorSelection,
+  ) -> Result<Vec<TranslatedText>> {
+    let transliterations = selection
+      .iter()
+      .filter_map(|&translator_index| self.translators[translator_index].transliteration())
+      .collect::<Vec<_>>();
+    let mut transliterated = index_transliterated(text, &transliterations).into_iter();
+
+    let mut versions: Vec<TranslatedText> = Vec::new();
+    for &translator_index in selection {
+      let translator = &self.translators[translator_index];
+      let indexed = match translator
+        .transliteration()
+        .and_then(|_| transliterated.next())
+      {
+        Some(indexed) => indexed,
+        None => translator.index_text(text)?,
+      };
+      match versions
+        .iter_mut()
+        .find(|version| version.indexed == indexed)
+      {
+        Some(version) => version.translators.push(translator_index),
+        None => versions.push(TranslatedText {
+          indexed,
+          translators: vec![translator_index],
+        }),
+      }
+    }
+    Ok(versions)
+  }
+
   pub fn normalize_queries(&self, query: &str) -> Result<Vec<String>> {
     self
       .translators
@@ -70,40 +274,174 @@ impl Translators {
   }
 }
 
+/// Translators picked by `auto` for Calibre language codes (ISO 639-2/3).
+const AUTO_TRANSLATORS: &[(&str, &[FilterTranslator])] = &[
+  (
+    "zho",
+    &[
+      FilterTranslator::ChinesePinyin,
+      FilterTranslator::ChineseVariant,
+    ],
+  ),
+  (
+    "chi",
+    &[
+      FilterTranslator::ChinesePinyin,
+      FilterTranslator::ChineseVariant,
+    ],
+  ),
+  (
+    "cmn",
+    &[
+      FilterTranslator::ChinesePinyin,
+      FilterTranslator::ChineseVariant,
+    ],
+  ),
+  (
+    "yue",
+    &[
+      FilterTranslator::ChinesePinyin,
+      FilterTranslator::ChineseVariant,
+    ],
+  ),
+  (
+    "lzh",
+    &[
+      FilterTranslator::ChinesePinyin,
+      FilterTranslator::ChineseVariant,
+    ],
+  ),
+  ("jpn", &[FilterTranslator::JapaneseRomaji]),
+  ("kor", &[FilterTranslator::KoreanLatin]),
+  ("deu", &[FilterTranslator::GermanLatin]),
+  ("ger", &[FilterTranslator::GermanLatin]),
+  ("fra", &[FilterTranslator::FrenchLatin]),
+  ("fre", &[FilterTranslator::FrenchLatin]),
+  ("spa", &[FilterTranslator::SpanishLatin]),
+  ("rus", &[FilterTranslator::RussianLatin]),
+  ("ukr", &[FilterTranslator::UkrainianLatin]),
+  ("srp", &[FilterTranslator::SerbianLatin]),
+  ("mkd", &[FilterTranslator::SerbianLatin]),
+  ("mac", &[FilterTranslator::SerbianLatin]),
+  ("ell", &[FilterTranslator::GreekLatin]),
+  ("gre", &[FilterTranslator::GreekLatin]),
+  ("grc", &[FilterTranslator::GreekLatin]),
+];
+
+fn language_translators(language: &str) -> &'static [FilterTranslator] {
+  AUTO_TRANSLATORS
+    .iter()
+    .find(|(code, _)| code.eq_ignore_ascii_case(language))
+    .map_or(&[], |(_, translators)| translators)
+}
+
 pub fn index_plain_text(text: &str) -> IndexedText {
   let mut indexed = String::new();
   let mut token_bounds = vec![0];
 
   for ch in text.chars().filter(|ch| !ch.is_whitespace()) {
-    for lower in ch.to_lowercase() {
-      indexed.push(lower);
+    for part in fold_char(ch).filter(|part| !part.is_whitespace()) {
+      // A separate voicing mark, such as half-width `ﾞ`, joins the kana before it.
+      match indexed.chars().last().and_then(|last| compose(last, part)) {
+        Some(composed) => {
+          indexed.pop();
+          indexed.push(composed);
+        }
+        None => indexed.extend(part.to_lowercase()),
+      }
     }
     token_bounds.push(indexed.chars().count());
   }
 
-  IndexedText {
-    text: indexed,
-    token_bounds,
-  }
+  IndexedText::per_char(indexed, token_bounds)
 }
 
 pub fn normalize_plain_query(query: &str) -> String {
   index_plain_text(query).text
 }
 
-fn index_by_char(text: &str, transliterate: fn(char) -> String) -> IndexedText {
-  let mut indexed = String::new();
-  let mut token_bounds = vec![0];
+pub fn sort_reading(text: &str, reading: SortReading) -> String {
+  match reading {
+    SortReading::Pinyin => pinyin::pinyin_reading(text),
+    SortReading::Kana => japanese::kana_reading(text),
+  }
+}
 
-  for ch in text.chars().filter(|ch| !ch.is_whitespace()) {
-    indexed.push_str(&translated_search_text(ch, &transliterate(ch)));
-    token_bounds.push(indexed.chars().count());
+/// Folds accents and compatibility forms like the plain index, keeping case and whitespace.
+pub fn fold_accents(text: &str) -> String {
+  text.chars().flat_map(fold_char).nfc().collect()
+}
+
+/// One char with compatibility forms decomposed and accent marks dropped, recomposed
+/// afterwards so kana keep their voicing. Hangul syllables are kept whole, since their
+/// jamo would match every syllable that starts with the same letters.
+fn fold_char(ch: char) -> impl Iterator<Item = char> {
+  let mut parts = Vec::new();
+  if is_hangul_syllable(ch) {
+    parts.push(ch);
+  } else {
+    decompose_compatible(ch, |part| {
+      if !is_diacritic_mark(part) {
+        parts.push(part);
+      }
+    });
   }
+  parts.into_iter().nfc()
+}
 
-  IndexedText {
-    text: indexed,
-    token_bounds,
+fn is_hangul_syllable(ch: char) -> bool {
+  matches!(ch, '\u{ac00}'..='\u{d7a3}')
+}
+
+/// Accent and vowel-point marks dropped by the plain index. Marks that change a letter's
+/// identity in its script, such as kana voicing or Indic vowel signs, are kept.
+fn is_diacritic_mark(ch: char) -> bool {
+  matches!(
+    ch,
+    '\u{0300}'..='\u{036f}'
+      | '\u{0591}'..='\u{05c7}'
+      | '\u{064b}'..='\u{065f}'
+      | '\u{0670}'
+      | '\u{1ab0}'..='\u{1aff}'
+      | '\u{1dc0}'..='\u{1dff}'
+      | '\u{20d0}'..='\u{20ff}'
+      | '\u{fe20}'..='\u{fe2f}'
+  )
+}
+
+/// Indexes `text` with several transliterations in one pass. Chars are collected and
+/// whitespace skipped once, and the Latin fallback is folded once per char.
+fn index_transliterated(text: &str, transliterations: &[Transliteration]) -> Vec<IndexedText> {
+  let chars = text.chars().collect::<Vec<_>>();
+  let mut indexed = vec![String::new(); transliterations.len()];
+  let mut lengths = vec![0; transliterations.len()];
+  let mut token_bounds = vec![vec![0]; transliterations.len()];
+
+  for (index, &ch) in chars.iter().enumerate() {
+    if ch.is_whitespace() {
+      continue;
+    }
+    let mut fallback = None;
+    for (slot, transliteration) in transliterations.iter().enumerate() {
+      let mapped;
+      let search_text = match transliteration.apply(&chars, index) {
+        Some(translated) => {
+          mapped = translated_search_text(ch, translated);
+          &mapped
+        }
+        None => fallback.get_or_insert_with(|| translated_search_text(ch, &latin_char(ch))),
+      };
+      indexed[slot].push_str(search_text);
+      lengths[slot] += search_text.chars().count();
+      token_bounds[slot].push(lengths[slot]);
+    }
   }
+
+  indexed
+    .into_iter()
+    .zip(token_bounds)
+    .map(|(text, token_bounds)| IndexedText::per_char(text, token_bounds))
+    .collect()
 }
 
 fn translated_search_text(source: char, translated: &str) -> String {
@@ -46,7 +46,11 @@ impl ChinesePinyinTranslator {
       token_bounds.push(text.chars().count());
     }
 
-    Ok(IndexedText { text, token_bounds })
+    Ok(IndexedText {
+      text,
+      token_bounds,
+      source_bounds: indexed.source_bounds.clone(),
+    })
   }
 
   fn apply_fuzzy_to_query(&self, query: &str) -> Result<String> {
@@ -101,10 +105,7 @@ impl Translator for ChinesePinyinTranslator {
       token_bounds.push(indexed.chars().count());
     }
 
-    self.apply_fuzzy_to_indexed_text(&IndexedText {
-      text: indexed,
-      token_bounds,
-    })
+    self.apply_fuzzy_to_indexed_text(&IndexedText::per_char(indexed, token_bounds))
   }
 
   fn normalize_query(&self, query: &str) -> Result<String> {
@@ -112,6 +113,20 @@ impl Translator for ChinesePinyinTranslator {
   }
 }
 
+/// Pinyin reading of a text without fuzzy folding. Whitespace is kept.
+pub(super) fn pinyin_reading(text: &str) -> String {
+  text
+    .chars()
+    .map(|ch| {
+      if ch.is_whitespace() {
+        ch.to_string()
+      } else {
+        char_to_search_text(ch)
+      }
+    })
+    .collect()
+}
+
 fn build_fuzzy_map(groups: &[Vec<String>]) -> HashMap<String, String> {
   let mut map = HashMap::new();
   for group in groups {
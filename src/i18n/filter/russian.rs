@@ -1,24 +1,26 @@
-use super::{IndexedText, Translator, fullwidth_ascii, index_by_char, latin_char};
+use super::{IndexedText, Translator, Transliteration};
 use anyhow::Result;
 
 pub(super) struct RussianLatinTranslator;
 
 impl Translator for RussianLatinTranslator {
   fn index_text(&self, text: &str) -> Result<IndexedText> {
-    Ok(index_by_char(text, russian_char))
+    Ok(RUSSIAN.index(text))
   }
 
   fn normalize_query(&self, query: &str) -> Result<String> {
-    Ok(index_by_char(query, russian_char).text)
+    Ok(RUSSIAN.index(query).text)
   }
-}
 
-fn russian_char(ch: char) -> String {
-  if let Some(converted) = fullwidth_ascii(ch) {
-    return converted.to_string();
+  fn transliteration(&self) -> Option<Transliteration> {
+    Some(RUSSIAN)
   }
+}
 
-  match ch {
+const RUSSIAN: Transliteration = Transliteration::ByChar(russian_char);
+
+fn russian_char(ch: char) -> Option<&'static str> {
+  Some(match ch {
     'А' | 'а' => "a",
     'Б' | 'б' => "b",
     'В' | 'в' => "v",
@@ -52,7 +54,6 @@ fn russian_char(ch: char) -> String {
     'Э' | 'э' => "e",
     'Ю' | 'ю' => "yu",
     'Я' | 'я' => "ya",
-    _ => return latin_char(ch),
-  }
-  .to_string()
+    _ => return None,
+  })
 }
@@ -0,0 +1,62 @@
+use super::{IndexedText, Translator, Transliteration};
+use anyhow::Result;
+
+pub(super) struct SerbianLatinTranslator;
+
+impl Translator for SerbianLatinTranslator {
+  fn index_text(&self, text: &str) -> Result<IndexedText> {
+    Ok(SERBIAN.index(text))
+  }
+
+  fn normalize_query(&self, query: &str) -> Result<String> {
+    Ok(SERBIAN.index(query).text)
+  }
+
+  fn transliteration(&self) -> Option<Transliteration> {
+    Some(SERBIAN)
+  }
+}
+
+const SERBIAN: Transliteration = Transliteration::ByChar(serbian_char);
+
+// Serbian Cyrillic maps one-to-one onto Gaj's Latin alphabet; its diacritics are
+// folded to the ASCII spellings commonly used in search (č, ć -> c, đ -> dj).
+// Macedonian letters follow the official Macedonian romanization.
+fn serbian_char(ch: char) -> Option<&'static str> {
+  Some(match ch {
+    'А' | 'а' => "a",
+    'Б' | 'б' => "b",
+    'В' | 'в' => "v",
+    'Г' | 'г' => "g",
+    'Д' | 'д' => "d",
+    'Ђ' | 'ђ' | 'Đ' | 'đ' => "dj",
+    'Ѓ' | 'ѓ' => "gj",
+    'Е' | 'е' => "e",
+    'Ж' | 'ж' => "z",
+    'З' | 'з' => "z",
+    'Ѕ' | 'ѕ' => "dz",
+    'И' | 'и' => "i",
+    'Ј' | 'ј' => "j",
+    'К' | 'к' => "k",
+    'Ќ' | 'ќ' => "kj",
+    'Л' | 'л' => "l",
+    'Љ' | 'љ' => "lj",
+    'М' | 'м' => "m",
+    'Н' | 'н' => "n",
+    'Њ' | 'њ' => "nj",
+    'О' | 'о' => "o",
+    'П' | 'п' => "p",
+    'Р' | 'р' => "r",
+    'С' | 'с' => "s",
+    'Т' | 'т' => "t",
+    'Ћ' | 'ћ' => "c",
+    'У' | 'у' => "u",
+    'Ф' | 'ф' => "f",
+    'Х' | 'х' => "h",
+    'Ц' | 'ц' => "c",
+    'Ч' | 'ч' => "c",
+    'Џ' | 'џ' => "dz",
+    'Ш' | 'ш' => "s",
+    _ => return None,
+  })
+}
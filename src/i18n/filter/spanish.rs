@@ -1,18 +1,25 @@
-use super::{IndexedText, Translator, index_by_char, latin_char};
+use super::{IndexedText, Translator, Transliteration};
 use anyhow::Result;
 
 pub(super) struct SpanishLatinTranslator;
 
 impl Translator for SpanishLatinTranslator {
   fn index_text(&self, text: &str) -> Result<IndexedText> {
-    Ok(index_by_char(text, spanish_char))
+    Ok(SPANISH.index(text))
   }
 
   fn normalize_query(&self, query: &str) -> Result<String> {
-    Ok(index_by_char(query, spanish_char).text)
+    Ok(SPANISH.index(query).text)
+  }
+
+  fn transliteration(&self) -> Option<Transliteration> {
+    Some(SPANISH)
   }
 }
 
-fn spanish_char(ch: char) -> String {
-  latin_char(ch)
+const SPANISH: Transliteration = Transliteration::ByChar(spanish_char);
+
+// Accented letters are covered by the shared Latin folding.
+fn spanish_char(_: char) -> Option<&'static str> {
+  None
 }
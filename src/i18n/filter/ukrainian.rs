@@ -0,0 +1,73 @@
+use super::{IndexedText, Translator, Transliteration};
+use anyhow::Result;
+
+pub(super) struct UkrainianLatinTranslator;
+
+impl Translator for UkrainianLatinTranslator {
+  fn index_text(&self, text: &str) -> Result<IndexedText> {
+    Ok(UKRAINIAN.index(text))
+  }
+
+  fn normalize_query(&self, query: &str) -> Result<String> {
+    Ok(UKRAINIAN.index(query).text)
+  }
+
+  fn transliteration(&self) -> Option<Transliteration> {
+    Some(UKRAINIAN)
+  }
+}
+
+const UKRAINIAN: Transliteration = Transliteration::ByContext(ukrainian_char);
+
+// Official transliteration adopted by the Cabinet of Ministers of Ukraine in 2010.
+fn ukrainian_char(chars: &[char], index: usize) -> Option<&'static str> {
+  let ch = chars[index];
+  let word_start = is_word_start(chars, index);
+  Some(match ch {
+    'А' | 'а' => "a",
+    'Б' | 'б' => "b",
+    'В' | 'в' => "v",
+    'Г' | 'г' if index > 0 && matches!(chars[index - 1], 'З' | 'з') => "gh",
+    'Г' | 'г' => "h",
+    'Ґ' | 'ґ' => "g",
+    'Д' | 'д' => "d",
+    'Е' | 'е' => "e",
+    'Є' | 'є' if word_start => "ye",
+    'Є' | 'є' => "ie",
+    'Ж' | 'ж' => "zh",
+    'З' | 'з' => "z",
+    'И' | 'и' => "y",
+    'І' | 'і' => "i",
+    'Ї' | 'ї' if word_start => "yi",
+    'Ї' | 'ї' => "i",
+    'Й' | 'й' if word_start => "y",
+    'Й' | 'й' => "i",
+    'К' | 'к' => "k",
+    'Л' | 'л' => "l",
+    'М' | 'м' => "m",
+    'Н' | 'н' => "n",
+    'О' | 'о' => "o",
+    'П' | 'п' => "p",
+    'Р' | 'р' => "r",
+    'С' | 'с' => "s",
+    'Т' | 'т' => "t",
+    'У' | 'у' => "u",
+    'Ф' | 'ф' => "f",
+    'Х' | 'х' => "kh",
+    'Ц' | 'ц' => "ts",
+    'Ч' | 'ч' => "ch",
+    'Ш' | 'ш' => "sh",
+    'Щ' | 'щ' => "shch",
+    'Ь' | 'ь' => "",
+    'Ю' | 'ю' if word_start => "yu",
+    'Ю' | 'ю' => "iu",
+    'Я' | 'я' if word_start => "ya",
+    'Я' | 'я' => "ia",
+    '\'' | '’' | 'ʼ' => "",
+    _ => return None,
+  })
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+  index == 0 || !(chars[index - 1].is_alphabetic() || matches!(chars[index - 1], '\'' | '’' | 'ʼ'))
+}
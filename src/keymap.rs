@@ -7,7 +7,11 @@ use serde::{Deserialize, Serialize};
 #[serde(deny_unknown_fields)]
 #[serde(default)]
 pub struct KeymapConfig {
+  /// Use the `normal` and `insert` sections instead of `browser`.
+  pub modal: bool,
   pub browser: KeymapSection,
+  pub normal: KeymapSection,
+  pub insert: KeymapSection,
   pub detail: KeymapSection,
   pub input: KeymapSection,
   pub global: KeymapSection,
@@ -28,6 +32,14 @@ pub struct KeymapEntry {
   pub desc: String,
 }
 
+/// Runtime bindings for each browser mode. Every set shares the input and global sections.
+pub struct Keymaps {
+  pub browser: KeyBindings,
+  pub normal: KeyBindings,
+  pub insert: KeyBindings,
+  pub modal: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum KeymapOn {
@@ -38,6 +50,7 @@ pub enum KeymapOn {
 impl Default for KeymapConfig {
   fn default() -> Self {
     Self {
+      modal: false,
       browser: KeymapSection {
         keymap: vec![
           key("esc", "quit", "Quit"),
@@ -45,6 +58,8 @@ impl Default for KeymapConfig {
           key("enter", "open", "Open selected books"),
           key("up", "move_up", "Move up"),
           key("down", "move_down", "Move down"),
+          key("alt-left", "move_left", "Move left in the grid"),
+          key("alt-right", "move_right", "Move right in the grid"),
           key("pgup", "page_up", "Move one page up"),
           key("pgdn", "page_down", "Move one page down"),
           key("pagedown", "page_down", "Move one page down"),
@@ -53,41 +68,29 @@ impl Default for KeymapConfig {
           key("tab", "toggle_selection", "Toggle selection"),
           key("ctrl-a", "select_all", "Select all results"),
           key("ctrl-x", "clear_selection", "Clear selection"),
+          key("ctrl-g", "toggle_group", "Collapse or expand group"),
+          key("ctrl-b", "toggle_facets", "Show or hide facet panel"),
+          key(
+            "ctrl-o",
+            "switch_focus",
+            "Switch focus between facets and results",
+          ),
           key("backspace", "delete_input", "Delete search input"),
+          key("alt-up", "history_previous", "Previous search history"),
+          key("alt-down", "history_next", "Next search history"),
+          key("ctrl-r", "history_search", "Search history"),
+          key("ctrl-f", "saved_searches", "Pick a saved search"),
+          key("ctrl-l", "libraries", "Pick a virtual library"),
+          key("ctrl-v", "views", "Pick a layout view"),
           key("ctrl-p", "print_paths", "Print selected paths and quit"),
           key("ctrl-y", "copy_paths", "Copy selected paths"),
-          key(["ctrl-s", "t"], "sort title asc", "Sort title ascending"),
-          key(["ctrl-s", "T"], "sort title desc", "Sort title descending"),
-          key(
-            ["ctrl-s", "a"],
-            "sort authors asc",
-            "Sort authors ascending",
-          ),
-          key(
-            ["ctrl-s", "A"],
-            "sort authors desc",
-            "Sort authors descending",
-          ),
-          key(["ctrl-s", "s"], "sort series asc", "Sort series ascending"),
-          key(
-            ["ctrl-s", "S"],
-            "sort series desc",
-            "Sort series descending",
-          ),
-          key(
-            ["ctrl-s", "f"],
-            "sort formats asc",
-            "Sort formats ascending",
-          ),
-          key(
-            ["ctrl-s", "F"],
-            "sort formats desc",
-            "Sort formats descending",
-          ),
-          key(["ctrl-s", "g"], "sort tags asc", "Sort tags ascending"),
-          key(["ctrl-s", "G"], "sort tags desc", "Sort tags descending"),
-        ],
+        ]
+        .into_iter()
+        .chain(default_sort_keys())
+        .collect(),
       },
+      normal: default_normal_keymap_section(),
+      insert: default_insert_keymap_section(),
       detail: KeymapSection::default(),
       input: default_input_keymap_section(),
       global: KeymapSection {
@@ -101,9 +104,19 @@ impl Default for KeymapConfig {
 }
 
 impl KeymapConfig {
-  pub fn bindings(&self) -> KeyBindings {
+  pub fn keymaps(&self) -> Keymaps {
+    Keymaps {
+      browser: self.bindings(&self.browser),
+      normal: self.bindings(&self.normal),
+      insert: self.bindings(&self.insert),
+      modal: self.modal,
+    }
+  }
+
+  /// Bindings with `section` in the browser context.
+  fn bindings(&self, section: &KeymapSection) -> KeyBindings {
     KeyBindings::from_sections(
-      binding_configs(&self.browser.keymap),
+      binding_configs(&section.keymap),
       binding_configs(&self.detail.keymap),
       binding_configs(&self.input.keymap),
       binding_configs(&self.global.keymap),
@@ -122,6 +135,12 @@ impl CommentedToml for KeymapConfig {
           "Key names include enter, esc, tab, backspace, up, down, left, right, home, end, pgup, pgdn, delete, insert, space, f1, single characters, ctrl-x, and alt-x.",
         ],
       },
+      TomlComment {
+        path: "modal",
+        lines: &[
+          "Vim-style modal navigation. When true, the normal and insert sections replace browser.",
+        ],
+      },
       TomlComment {
         path: "browser",
         lines: &["Active while browsing and searching books."],
@@ -142,6 +161,14 @@ impl CommentedToml for KeymapConfig {
         path: "browser.keymap.desc",
         lines: &["Description shown in F1 help and which-key hints."],
       },
+      TomlComment {
+        path: "normal",
+        lines: &["Modal normal mode. Typed characters do not edit the search; digits set a count."],
+      },
+      TomlComment {
+        path: "insert",
+        lines: &["Modal insert mode. Unbound characters are typed into the search."],
+      },
       TomlComment {
         path: "detail",
         lines: &["Reserved for detail views. Leave empty if unused."],
@@ -166,13 +193,111 @@ impl CommentedToml for KeymapConfig {
   }
 }
 
-pub fn load_keymap() -> Result<KeyBindings> {
+pub fn load_keymap() -> Result<Keymaps> {
   let config_dir = app_config_dir()?;
   let keymap_path = config_dir.join("keymap.toml");
   let config: KeymapConfig = load_toml_or_reset(&keymap_path, KeymapConfig::default(), "keymap")
     .with_context(|| format!("failed to load keymap file '{}'", keymap_path.display()))?;
 
-  Ok(config.bindings())
+  Ok(config.keymaps())
+}
+
+fn default_sort_keys() -> Vec<KeymapEntry> {
+  vec![
+    key(["ctrl-s", "t"], "sort title asc", "Sort title ascending"),
+    key(["ctrl-s", "T"], "sort title desc", "Sort title descending"),
+    key(
+      ["ctrl-s", "a"],
+      "sort authors asc",
+      "Sort authors ascending",
+    ),
+    key(
+      ["ctrl-s", "A"],
+      "sort authors desc",
+      "Sort authors descending",
+    ),
+    key(["ctrl-s", "s"], "sort series asc", "Sort series ascending"),
+    key(
+      ["ctrl-s", "S"],
+      "sort series desc",
+      "Sort series descending",
+    ),
+    key(
+      ["ctrl-s", "f"],
+      "sort formats asc",
+      "Sort formats ascending",
+    ),
+    key(
+      ["ctrl-s", "F"],
+      "sort formats desc",
+      "Sort formats descending",
+    ),
+    key(["ctrl-s", "g"], "sort tags asc", "Sort tags ascending"),
+    key(["ctrl-s", "G"], "sort tags desc", "Sort tags descending"),
+  ]
+}
+
+fn default_normal_keymap_section() -> KeymapSection {
+  KeymapSection {
+    keymap: vec![
+      key("q", "quit", "Quit"),
+      key("ctrl-c", "quit", "Quit"),
+      key("enter", "open", "Open selected books"),
+      key("j", "move_down", "Move down"),
+      key("down", "move_down", "Move down"),
+      key("k", "move_up", "Move up"),
+      key("up", "move_up", "Move up"),
+      key("h", "move_left", "Move left in the grid"),
+      key("l", "move_right", "Move right in the grid"),
+      key("ctrl-d", "page_down", "Move one page down"),
+      key("pgdn", "page_down", "Move one page down"),
+      key("ctrl-u", "page_up", "Move one page up"),
+      key("pgup", "page_up", "Move one page up"),
+      key(["g", "g"], "jump_start", "Jump to first result or count"),
+      key("home", "jump_start", "Jump to first result"),
+      key("G", "jump_end", "Jump to last result or count"),
+      key("end", "jump_end", "Jump to last result"),
+      key("/", "search", "Start a new search"),
+      key("i", "insert_mode", "Edit the search"),
+      key("ctrl-r", "history_search", "Search history"),
+      key("ctrl-f", "saved_searches", "Pick a saved search"),
+      key("ctrl-l", "libraries", "Pick a virtual library"),
+      key("ctrl-v", "views", "Pick a layout view"),
+      key("n", "next_match", "Next highlighted match"),
+      key("N", "previous_match", "Previous highlighted match"),
+      key("space", "toggle_selection", "Toggle selection"),
+      key("tab", "toggle_selection", "Toggle selection"),
+      key("ctrl-a", "select_all", "Select all results"),
+      key("ctrl-x", "clear_selection", "Clear selection"),
+      key("ctrl-g", "toggle_group", "Collapse or expand group"),
+      key("ctrl-b", "toggle_facets", "Show or hide facet panel"),
+      key(
+        "ctrl-o",
+        "switch_focus",
+        "Switch focus between facets and results",
+      ),
+      key("y", "copy_paths", "Copy selected paths"),
+      key("ctrl-p", "print_paths", "Print selected paths and quit"),
+      key(":", "command", "Enter command"),
+    ]
+    .into_iter()
+    .chain(default_sort_keys())
+    .collect(),
+  }
+}
+
+fn default_insert_keymap_section() -> KeymapSection {
+  KeymapSection {
+    keymap: vec![
+      key("esc", "normal_mode", "Back to normal mode"),
+      key("enter", "normal_mode", "Back to normal mode"),
+      key("ctrl-c", "quit", "Quit"),
+      key("up", "history_previous", "Previous search history"),
+      key("down", "history_next", "Next search history"),
+      key("ctrl-r", "history_search", "Search history"),
+      key("backspace", "delete_input", "Delete search input"),
+    ],
+  }
 }
 
 fn default_input_keymap_section() -> KeymapSection {
@@ -180,6 +305,8 @@ fn default_input_keymap_section() -> KeymapSection {
     keymap: vec![
       key("f1", "help", "Show key bindings"),
       key("esc", "cancel", "Cancel input"),
+      key("ctrl-c", "cancel", "Cancel input"),
+      key("ctrl-g", "cancel", "Cancel input"),
       key("enter", "submit", "Submit input"),
       key("backspace", "backspace", "Delete before cursor"),
       key("delete", "delete", "Delete under cursor"),
@@ -187,10 +314,20 @@ fn default_input_keymap_section() -> KeymapSection {
       key("right", "move_right", "Move cursor right"),
       key("home", "move_start", "Move cursor to start"),
       key("ctrl-a", "move_start", "Move cursor to start"),
+      key("alt-a", "move_start", "Move cursor to start"),
       key("end", "move_end", "Move cursor to end"),
       key("ctrl-e", "move_end", "Move cursor to end"),
+      key("alt-b", "move_word_left", "Move cursor one word left"),
+      key("alt-f", "move_word_right", "Move cursor one word right"),
+      key(
+        "ctrl-w",
+        "kill_word_before_cursor",
+        "Delete word before cursor",
+      ),
       key("ctrl-u", "kill_before_cursor", "Delete before cursor"),
       key("ctrl-k", "kill_after_cursor", "Delete after cursor"),
+      key("ctrl-z", "undo", "Undo edit"),
+      key("alt-z", "redo", "Redo edit"),
       key("tab", "completion_next", "Select next completion"),
       key(
         "backtab",
@@ -199,6 +336,10 @@ fn default_input_keymap_section() -> KeymapSection {
       ),
       key("up", "history_previous", "Previous command history"),
       key("down", "history_next", "Next command history"),
+      key("ctrl-r", "history_search", "Search command history"),
+      key("ctrl-p", "move_up", "Previous picker item"),
+      key("ctrl-s", "move_up", "Previous picker item"),
+      key("ctrl-n", "move_down", "Next picker item"),
     ],
   }
 }
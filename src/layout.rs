@@ -1,25 +1,85 @@
 use crate::config_file::{
   CommentedToml, TomlComment, app_config_dir, load_toml_or_reset_with, serialize_with_comments,
+  write_config,
 };
+use crate::sort::SortSpec;
+use crate::template::Template;
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 
+/// Relative width of a column added with `column show`.
+const NEW_COLUMN_WIDTH: u16 = 15;
+
+/// Name of the view defined by the top-level `[[columns]]` entries.
+pub const DEFAULT_VIEW: &str = "default";
+
+/// All views from `layout.toml`: the default one and the named `[views.<name>]` tables.
+#[derive(Debug, Clone)]
+pub struct Layouts {
+  default: Layout,
+  views: BTreeMap<String, Layout>,
+}
+
+/// One view: table columns, search fields and an optional default sort.
 #[derive(Debug, Clone)]
 pub struct Layout {
   columns: Vec<LayoutColumn>,
+  /// Sort applied when switching to this view.
+  pub sort: Option<SortSpec>,
 }
 
 #[derive(Debug, Clone)]
 pub struct LayoutColumn {
-  pub field: BookField,
+  pub key: ColumnKey,
+  /// Set for template columns.
+  pub template: Option<Template>,
   pub label: String,
   pub visible: bool,
   pub search: bool,
   pub width: u16,
+  /// Treat `width` as a cell count instead of a proportion.
+  pub fixed: bool,
+  /// Smallest cell width of a proportional column.
+  pub min_width: u16,
+  pub align: ColumnAlign,
+  pub ellipsis: Ellipsis,
+  /// Show only the last part of hierarchical tags.
+  pub leaf_names: bool,
+  /// Separator between values of list fields, instead of `, ` or ` & `.
+  pub separator: Option<String>,
+  /// Show at most this many values of a list field, followed by `+N more`. `0` shows all.
+  pub max_values: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColumnAlign {
+  #[default]
+  Left,
+  Right,
+  Center,
+}
+
+/// Where `…` replaces text that does not fit the column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ellipsis {
+  #[default]
+  End,
+  Middle,
+}
+
+/// What a column shows: a book field, or a template column defined in `layout.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColumnKey {
+  Field(BookField),
+  /// Template column name, written as `#name` in commands and sort keys.
+  Template(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum BookField {
   Title,
@@ -27,19 +87,38 @@ pub enum BookField {
   Series,
   Formats,
   Tags,
+  Publisher,
+  Languages,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 #[serde(default)]
 struct LayoutConfig {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  sort: Option<String>,
+  columns: Vec<LayoutColumnConfig>,
+  #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+  views: BTreeMap<String, LayoutViewConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LayoutViewConfig {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  sort: Option<String>,
   columns: Vec<LayoutColumnConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 struct LayoutColumnConfig {
-  field: BookField,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  field: Option<BookField>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  name: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  template: Option<String>,
   #[serde(default)]
   label: Option<String>,
   #[serde(default = "default_true")]
@@ -48,6 +127,62 @@ struct LayoutColumnConfig {
   search: bool,
   #[serde(default = "default_width")]
   width: u16,
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  fixed: bool,
+  #[serde(default, skip_serializing_if = "is_default")]
+  min_width: u16,
+  #[serde(default, skip_serializing_if = "is_default")]
+  align: ColumnAlign,
+  #[serde(default, skip_serializing_if = "is_default")]
+  ellipsis: Ellipsis,
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  leaf_names: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  separator: Option<String>,
+  #[serde(default, skip_serializing_if = "is_default")]
+  max_values: usize,
+}
+
+impl Layouts {
+  /// View names, with the default view first.
+  pub fn names(&self) -> Vec<&str> {
+    std::iter::once(DEFAULT_VIEW)
+      .chain(self.views.keys().map(String::as_str))
+      .collect()
+  }
+
+  pub fn get(&self, name: &str) -> Option<&Layout> {
+    if name == DEFAULT_VIEW {
+      Some(&self.default)
+    } else {
+      self.views.get(name)
+    }
+  }
+
+  /// Replaces an existing view, such as after runtime column changes.
+  pub fn set(&mut self, name: &str, layout: Layout) {
+    if name == DEFAULT_VIEW {
+      self.default = layout;
+    } else if let Some(view) = self.views.get_mut(name) {
+      *view = layout;
+    }
+  }
+
+  fn to_config(&self) -> LayoutConfig {
+    let (sort, columns) = self.default.to_config();
+    LayoutConfig {
+      sort,
+      columns,
+      views: self
+        .views
+        .iter()
+        .map(|(name, layout)| {
+          let (sort, columns) = layout.to_config();
+          (name.clone(), LayoutViewConfig { sort, columns })
+        })
+        .collect(),
+    }
+  }
 }
 
 impl Layout {
@@ -55,12 +190,216 @@ impl Layout {
     self.columns.iter().filter(|column| column.visible)
   }
 
-  pub fn search_fields(&self) -> impl Iterator<Item = BookField> + '_ {
+  /// Sets the relative widths of visible columns, in display order.
+  pub fn set_visible_widths(&mut self, widths: &[u16]) {
+    for (column, width) in self
+      .columns
+      .iter_mut()
+      .filter(|column| column.visible)
+      .zip(widths)
+    {
+      column.width = (*width).max(1);
+    }
+  }
+
+  pub fn column(&self, key: &ColumnKey) -> Option<&LayoutColumn> {
+    self.columns.iter().find(|column| column.key == *key)
+  }
+
+  /// Names of the template columns, with their `#` prefix.
+  pub fn template_names(&self) -> Vec<String> {
+    self
+      .columns
+      .iter()
+      .filter(|column| column.template.is_some())
+      .map(|column| column.key.name())
+      .collect()
+  }
+
+  pub fn hide_column(&mut self, key: &ColumnKey) -> Result<()> {
+    let Some(index) = self.visible_index(key) else {
+      bail!("{} is not shown", key.name());
+    };
+    if self.visible_columns().count() == 1 {
+      bail!("cannot hide the last visible column");
+    }
+    self.columns[index].visible = false;
+    Ok(())
+  }
+
+  /// Shows a hidden column, or adds a searchable column at the end for a field the layout
+  /// does not have yet. Template columns must be defined in `layout.toml` first.
+  pub fn show_column(&mut self, key: &ColumnKey) -> Result<()> {
+    match self.columns.iter_mut().find(|column| column.key == *key) {
+      Some(column) if column.visible => bail!("{} is already shown", key.name()),
+      Some(column) => {
+        column.visible = true;
+        if column.width == 0 {
+          column.width = NEW_COLUMN_WIDTH;
+        }
+      }
+      None => {
+        let ColumnKey::Field(field) = key else {
+          bail!("template column {} is not defined in this view", key.name());
+        };
+        self.columns.push(LayoutColumn::field(*field));
+      }
+    }
+    Ok(())
+  }
+
+  /// Moves a visible column to a 1-based position among the visible columns.
+  pub fn move_column(&mut self, key: &ColumnKey, position: usize) -> Result<()> {
+    let Some(index) = self.visible_index(key) else {
+      bail!("{} is not shown", key.name());
+    };
+    let visible_count = self.visible_columns().count();
+    if position == 0 || position > visible_count {
+      bail!("column position must be between 1 and {visible_count}");
+    }
+
+    let column = self.columns.remove(index);
+    let target = self
+      .columns
+      .iter()
+      .enumerate()
+      .filter(|(_, column)| column.visible)
+      .map(|(index, _)| index)
+      .nth(position - 1)
+      .unwrap_or(self.columns.len());
+    self.columns.insert(target, column);
+    Ok(())
+  }
+
+  pub fn set_column_width(&mut self, key: &ColumnKey, width: u16) -> Result<()> {
+    if width == 0 {
+      bail!("column width must be greater than 0");
+    }
+    let Some(column) = self.columns.iter_mut().find(|column| column.key == *key) else {
+      bail!("{} is not in the layout", key.name());
+    };
+    column.width = width;
+    Ok(())
+  }
+
+  fn visible_index(&self, key: &ColumnKey) -> Option<usize> {
+    self
+      .columns
+      .iter()
+      .position(|column| column.key == *key && column.visible)
+  }
+
+  fn to_config(&self) -> (Option<String>, Vec<LayoutColumnConfig>) {
+    let columns = self
+      .columns
+      .iter()
+      .map(|column| LayoutColumnConfig {
+        field: column.key.field(),
+        name: column
+          .template
+          .is_some()
+          .then(|| column.key.name().trim_start_matches('#').to_string()),
+        template: column
+          .template
+          .as_ref()
+          .map(|template| template.source().to_string()),
+        label: Some(column.label.clone()),
+        visible: column.visible,
+        search: column.search,
+        width: column.width,
+        fixed: column.fixed,
+        min_width: column.min_width,
+        align: column.align,
+        ellipsis: column.ellipsis,
+        leaf_names: column.leaf_names,
+        separator: column.separator.clone(),
+        max_values: column.max_values,
+      })
+      .collect();
+    (self.sort.as_ref().map(SortSpec::command_args), columns)
+  }
+
+  pub fn search_fields(&self) -> impl Iterator<Item = &ColumnKey> {
+    self
+      .columns
+      .iter()
+      .filter(|column| column.search)
+      .map(|column| &column.key)
+  }
+
+  /// Searchable template columns by name.
+  pub fn search_templates(&self) -> impl Iterator<Item = (&str, &Template)> {
+    self
+      .columns
+      .iter()
+      .filter(|column| column.search)
+      .filter_map(|column| {
+        let ColumnKey::Template(name) = &column.key else {
+          return None;
+        };
+        Some((name.as_str(), column.template.as_ref()?))
+      })
+  }
+
+  /// Whether both layouts search the same columns in the same order, with the same
+  /// templates.
+  pub fn same_search(&self, other: &Layout) -> bool {
+    self.search_columns() == other.search_columns()
+  }
+
+  fn search_columns(&self) -> Vec<(&ColumnKey, Option<&str>)> {
     self
       .columns
       .iter()
       .filter(|column| column.search)
-      .map(|column| column.field)
+      .map(|column| (&column.key, column.template.as_ref().map(Template::source)))
+      .collect()
+  }
+}
+
+impl LayoutColumn {
+  /// A visible, searchable column for a field with default display options.
+  pub fn field(field: BookField) -> Self {
+    Self {
+      key: ColumnKey::Field(field),
+      template: None,
+      label: field.default_label().to_string(),
+      visible: true,
+      search: true,
+      width: NEW_COLUMN_WIDTH,
+      fixed: false,
+      min_width: 0,
+      align: ColumnAlign::default(),
+      ellipsis: Ellipsis::default(),
+      leaf_names: false,
+      separator: None,
+      max_values: 0,
+    }
+  }
+}
+
+impl ColumnKey {
+  /// Parses a book field name, or `#name` for a template column.
+  pub fn parse(input: &str) -> Option<Self> {
+    match input.strip_prefix('#') {
+      Some(name) if !name.is_empty() => Some(Self::Template(name.to_string())),
+      Some(_) => None,
+      None => BookField::parse(input).map(Self::Field),
+    }
+  }
+
+  pub fn name(&self) -> String {
+    match self {
+      Self::Field(field) => field.name().to_string(),
+      Self::Template(name) => format!("#{name}"),
+    }
+  }
+
+  pub fn field(&self) -> Option<BookField> {
+    match self {
+      Self::Field(field) => Some(*field),
+      Self::Template(_) => None,
+    }
   }
 }
 
@@ -72,6 +411,8 @@ impl BookField {
       "series" => Some(Self::Series),
       "format" | "formats" => Some(Self::Formats),
       "tag" | "tags" => Some(Self::Tags),
+      "publisher" | "publishers" => Some(Self::Publisher),
+      "language" | "languages" => Some(Self::Languages),
       _ => None,
     }
   }
@@ -83,9 +424,19 @@ impl BookField {
       Self::Series => "series",
       Self::Formats => "formats",
       Self::Tags => "tags",
+      Self::Publisher => "publisher",
+      Self::Languages => "languages",
     }
   }
 
+  /// Whether the field holds several values, such as authors or tags.
+  pub fn is_list(self) -> bool {
+    matches!(
+      self,
+      Self::Authors | Self::Formats | Self::Tags | Self::Languages
+    )
+  }
+
   fn default_label(self) -> &'static str {
     self.name()
   }
@@ -94,6 +445,7 @@ impl BookField {
 impl Default for LayoutConfig {
   fn default() -> Self {
     Self {
+      sort: None,
       columns: vec![
         LayoutColumnConfig::new(BookField::Title, 35),
         LayoutColumnConfig::new(BookField::Authors, 20),
@@ -101,6 +453,26 @@ impl Default for LayoutConfig {
         LayoutColumnConfig::new(BookField::Formats, 12),
         LayoutColumnConfig::new(BookField::Tags, 15),
       ],
+      views: BTreeMap::new(),
+    }
+  }
+}
+
+/// The built-in columns, without reading `layout.toml`.
+impl Default for Layout {
+  fn default() -> Self {
+    let columns = LayoutConfig::default()
+      .columns
+      .into_iter()
+      .filter_map(|config| {
+        let mut column = LayoutColumn::field(config.field?);
+        column.width = config.width;
+        Some(column)
+      })
+      .collect();
+    Self {
+      columns,
+      sort: None,
     }
   }
 }
@@ -108,11 +480,20 @@ impl Default for LayoutConfig {
 impl LayoutColumnConfig {
   fn new(field: BookField, width: u16) -> Self {
     Self {
-      field,
+      field: Some(field),
+      name: None,
+      template: None,
       label: None,
       visible: true,
       search: true,
       width,
+      fixed: false,
+      min_width: 0,
+      align: ColumnAlign::default(),
+      ellipsis: Ellipsis::default(),
+      leaf_names: false,
+      separator: None,
+      max_values: 0,
     }
   }
 }
@@ -125,7 +506,20 @@ impl CommentedToml for LayoutConfig {
         lines: &[
           "Table column layout.",
           "The order of [[columns]] entries controls table order and search match priority.",
-          "Supported fields: title, authors, series, formats, tags.",
+          "Supported fields: title, authors, series, formats, tags, publisher, languages.",
+          "Template columns set name and template instead of field, for example",
+          "name = \"shelf\" and template = \"{series}{series_index:| [|]}\".",
+          "Optional column keys: fixed, min_width, align, ellipsis, separator, max_values,",
+          "leaf_names.",
+          "Add [views.<name>] tables with their own [[views.<name>.columns]] and optional sort",
+          "for more views, switched with the view command.",
+        ],
+      },
+      TomlComment {
+        path: "sort",
+        lines: &[
+          "Sort applied when switching to this view, in sort command syntax. The top-level sort",
+          "also replaces sort.default at startup. Without it, the current sort is kept.",
         ],
       },
       TomlComment {
@@ -136,6 +530,16 @@ impl CommentedToml for LayoutConfig {
         path: "columns.field",
         lines: &["Book metadata field represented by this column."],
       },
+      TomlComment {
+        path: "columns.name",
+        lines: &["Template column name, used as #name in sort and column commands."],
+      },
+      TomlComment {
+        path: "columns.template",
+        lines: &[
+          "Column text built from book fields, such as \"{authors:first}\" or \"{pubdate:%Y}\".",
+        ],
+      },
       TomlComment {
         path: "columns.label",
         lines: &["Column title displayed in the table header."],
@@ -152,14 +556,50 @@ impl CommentedToml for LayoutConfig {
         path: "columns.width",
         lines: &["Relative table width. Values are proportions and do not need to add up to 100."],
       },
+      TomlComment {
+        path: "columns.fixed",
+        lines: &["Use width as a number of terminal cells instead of a proportion."],
+      },
+      TomlComment {
+        path: "columns.min_width",
+        lines: &["Smallest width in cells of a proportional column."],
+      },
+      TomlComment {
+        path: "columns.align",
+        lines: &["Text alignment: left, right, or center."],
+      },
+      TomlComment {
+        path: "columns.ellipsis",
+        lines: &["Where \"…\" replaces text that does not fit: end or middle."],
+      },
+      TomlComment {
+        path: "columns.separator",
+        lines: &["Text between values of authors, formats, tags, and languages."],
+      },
+      TomlComment {
+        path: "columns.max_values",
+        lines: &["Show at most this many values, followed by \"+N more\". 0 shows all."],
+      },
+      TomlComment {
+        path: "columns.leaf_names",
+        lines: &["Show only the last part of hierarchical tags; tags column only."],
+      },
     ]
   }
 
   fn to_commented_toml(&self) -> Result<String> {
     let mut normalized = self.clone();
-    for column in &mut normalized.columns {
+    for column in normalized.columns.iter_mut().chain(
+      normalized
+        .views
+        .values_mut()
+        .flat_map(|view| &mut view.columns),
+    ) {
       if column.label.is_none() {
-        column.label = Some(column.field.default_label().to_string());
+        column.label = match (column.field, &column.name) {
+          (Some(field), _) => Some(field.default_label().to_string()),
+          (None, name) => name.clone(),
+        };
       }
     }
     serialize_with_comments(&normalized, Self::comments())
@@ -167,47 +607,113 @@ impl CommentedToml for LayoutConfig {
 }
 
 impl LayoutConfig {
-  fn compile(self) -> Result<Layout> {
-    if self.columns.is_empty() {
-      bail!("layout must define at least one column");
+  fn compile(self) -> Result<Layouts> {
+    let default = compile_view(self.columns, self.sort)?;
+    let mut views = BTreeMap::new();
+    for (name, view) in self.views {
+      if name == DEFAULT_VIEW || name.trim().is_empty() {
+        bail!("invalid view name '{name}'");
+      }
+      let layout =
+        compile_view(view.columns, view.sort).with_context(|| format!("invalid view '{name}'"))?;
+      views.insert(name, layout);
     }
+    Ok(Layouts { default, views })
+  }
+}
 
-    let mut seen = BTreeSet::new();
-    let mut columns = Vec::new();
-    for column in self.columns {
-      if !seen.insert(column.field) {
-        bail!("duplicate layout column '{:?}'", column.field);
-      }
+fn compile_view(columns_config: Vec<LayoutColumnConfig>, sort: Option<String>) -> Result<Layout> {
+  if columns_config.is_empty() {
+    bail!("layout must define at least one column");
+  }
 
-      if column.visible && column.width == 0 {
-        bail!(
-          "visible layout column '{:?}' must have width > 0",
-          column.field
-        );
+  let mut seen = BTreeSet::new();
+  let mut columns = Vec::new();
+  for column in columns_config {
+    let (key, template, default_label) = match (column.field, column.name, column.template) {
+      (Some(field), None, None) => (
+        ColumnKey::Field(field),
+        None,
+        field.default_label().to_string(),
+      ),
+      (None, Some(name), Some(template)) => {
+        if name.is_empty() || name.contains(|ch: char| ch.is_whitespace() || "#:\"".contains(ch)) {
+          bail!("invalid template column name '{name}'");
+        }
+        let template = Template::parse(&template)
+          .with_context(|| format!("invalid template for column '#{name}'"))?;
+        (ColumnKey::Template(name.clone()), Some(template), name)
       }
+      (Some(field), _, _) => bail!(
+        "layout column '{}' cannot also set name or template",
+        field.name()
+      ),
+      (None, _, _) => bail!("layout column needs a field, or both a name and a template"),
+    };
+    if !seen.insert(key.clone()) {
+      bail!("duplicate layout column '{}'", key.name());
+    }
 
-      columns.push(LayoutColumn {
-        field: column.field,
-        label: column
-          .label
-          .unwrap_or_else(|| column.field.default_label().to_string()),
-        visible: column.visible,
-        search: column.search,
-        width: column.width,
-      });
+    if column.leaf_names && key != ColumnKey::Field(BookField::Tags) {
+      bail!("leaf_names only applies to the tags column");
     }
 
-    if !columns.iter().any(|column| column.visible) {
-      bail!("layout must have at least one visible column");
+    if (column.separator.is_some() || column.max_values > 0)
+      && !key.field().is_some_and(BookField::is_list)
+    {
+      bail!(
+        "separator and max_values only apply to authors, formats, tags and languages, not {}",
+        key.name()
+      );
     }
 
-    Ok(Layout { columns })
+    if column.visible && column.width == 0 {
+      bail!("visible layout column '{}' must have width > 0", key.name());
+    }
+
+    columns.push(LayoutColumn {
+      key,
+      template,
+      label: column.label.unwrap_or(default_label),
+      visible: column.visible,
+      search: column.search,
+      width: column.width,
+      fixed: column.fixed,
+      min_width: column.min_width,
+      align: column.align,
+      ellipsis: column.ellipsis,
+      leaf_names: column.leaf_names,
+      separator: column.separator,
+      max_values: column.max_values,
+    });
   }
+
+  if !columns.iter().any(|column| column.visible) {
+    bail!("layout must have at least one visible column");
+  }
+
+  let sort = sort
+    .map(|sort| {
+      SortSpec::parse(&sort.split_whitespace().collect::<Vec<_>>())
+        .with_context(|| format!("invalid sort '{sort}'"))
+    })
+    .transpose()?;
+  let layout = Layout { columns, sort };
+  if let Some(sort) = &layout.sort
+    && let Some(key) = sort
+      .columns()
+      .find(|key| key.field().is_none() && layout.column(key).is_none())
+  {
+    bail!(
+      "sort uses template column {} that this view does not define",
+      key.name()
+    );
+  }
+  Ok(layout)
 }
 
-pub fn load_layout() -> Result<Layout> {
-  let config_dir = app_config_dir()?;
-  let layout_path = config_dir.join("layout.toml");
+pub fn load_layout() -> Result<Layouts> {
+  let layout_path = layout_path()?;
   load_toml_or_reset_with(
     &layout_path,
     LayoutConfig::default(),
@@ -217,6 +723,17 @@ pub fn load_layout() -> Result<Layout> {
   .with_context(|| format!("failed to load layout file '{}'", layout_path.display()))
 }
 
+/// Writes every view to `layout.toml` with the usual comments and returns the path.
+pub fn save_layout(layouts: &Layouts) -> Result<PathBuf> {
+  let layout_path = layout_path()?;
+  write_config(&layout_path, &layouts.to_config())?;
+  Ok(layout_path)
+}
+
+fn layout_path() -> Result<PathBuf> {
+  Ok(app_config_dir()?.join("layout.toml"))
+}
+
 fn default_true() -> bool {
   true
 }
@@ -224,3 +741,7 @@ fn default_true() -> bool {
 fn default_width() -> u16 {
   1
 }
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+  *value == T::default()
+}
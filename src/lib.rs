@@ -0,0 +1,20 @@
+pub mod app;
+pub mod config;
+pub mod config_file;
+pub mod cover;
+pub mod facet;
+pub mod filter;
+pub mod group;
+pub mod history;
+pub mod i18n;
+pub mod keymap;
+pub mod layout;
+pub mod line_edit;
+pub mod picker;
+pub mod saved_search;
+pub mod session;
+pub mod sort;
+pub mod template;
+pub mod theme;
+pub mod ui;
+pub mod utils;
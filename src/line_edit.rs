@@ -0,0 +1,203 @@
+/// Single-line text buffer with a cursor and undo history. Editing actions use the names of
+/// the `input` keymap context, so the search box and command prompt share bindings.
+///
+/// framework-tui's `Prompt` is not used here: its buffer is read-only outside
+/// `handle_prompt_key`, which resolves keys itself and applies `submit`, `cancel` and
+/// history to the command prompt state. The search box needs browser keys first and its
+/// text replaced by history recall, saved searches and pickers.
+#[derive(Debug, Clone, Default)]
+pub struct LineEditor {
+  text: String,
+  /// Byte offset, always on a char boundary.
+  cursor: usize,
+  undo: Vec<Snapshot>,
+  redo: Vec<Snapshot>,
+  /// Kind of the last edit, so typing a word is undone as one step.
+  last_edit: Option<EditKind>,
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+  text: String,
+  cursor: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+  Insert,
+  Delete,
+}
+
+/// Result of applying an `input` keymap action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditResult {
+  /// The text changed.
+  Changed,
+  /// Only the cursor moved, or nothing happened.
+  Unchanged,
+  /// The action does not edit text, such as `submit` or `history_previous`.
+  NotEditing,
+}
+
+impl LineEditor {
+  pub fn new(text: String) -> Self {
+    Self {
+      cursor: text.len(),
+      text,
+      ..Self::default()
+    }
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn cursor(&self) -> usize {
+    self.cursor
+  }
+
+  /// Replaces the whole text as one undoable edit and moves the cursor to the end.
+  pub fn set_text(&mut self, text: String) {
+    if text == self.text {
+      return;
+    }
+    self.record(None);
+    self.cursor = text.len();
+    self.text = text;
+  }
+
+  pub fn insert_char(&mut self, ch: char) {
+    // Whitespace starts a new undo step, so words are undone one at a time.
+    if ch.is_whitespace() {
+      self.last_edit = None;
+    }
+    self.record(Some(EditKind::Insert));
+    self.text.insert(self.cursor, ch);
+    self.cursor += ch.len_utf8();
+  }
+
+  /// Inserts pasted text, joining lines with spaces.
+  pub fn insert_str(&mut self, text: &str) {
+    let text = text
+      .lines()
+      .map(str::trim_end)
+      .collect::<Vec<_>>()
+      .join(" ");
+    if text.is_empty() {
+      return;
+    }
+    self.record(None);
+    self.text.insert_str(self.cursor, &text);
+    self.cursor += text.len();
+  }
+
+  pub fn apply(&mut self, action: &str) -> EditResult {
+    let (start, end) = match action {
+      "move_left" => return self.move_to(self.previous_boundary()),
+      "move_right" => return self.move_to(self.next_boundary()),
+      "move_word_left" => return self.move_to(self.word_start()),
+      "move_word_right" => return self.move_to(self.word_end()),
+      "move_start" => return self.move_to(0),
+      "move_end" => return self.move_to(self.text.len()),
+      "undo" => return self.undo(),
+      "redo" => return self.redo(),
+      "backspace" => (self.previous_boundary(), self.cursor),
+      "delete" => (self.cursor, self.next_boundary()),
+      "kill_word_before_cursor" => (self.word_start(), self.cursor),
+      "kill_before_cursor" => (0, self.cursor),
+      "kill_after_cursor" => (self.cursor, self.text.len()),
+      _ => return EditResult::NotEditing,
+    };
+
+    if start == end {
+      return EditResult::Unchanged;
+    }
+    let kind = matches!(action, "backspace" | "delete").then_some(EditKind::Delete);
+    self.record(kind);
+    self.text.replace_range(start..end, "");
+    self.cursor = start;
+    EditResult::Changed
+  }
+
+  fn move_to(&mut self, cursor: usize) -> EditResult {
+    self.cursor = cursor;
+    self.last_edit = None;
+    EditResult::Unchanged
+  }
+
+  /// Saves the current state for undo unless this edit continues the previous one.
+  fn record(&mut self, kind: Option<EditKind>) {
+    if kind.is_none() || kind != self.last_edit {
+      self.undo.push(self.snapshot());
+    }
+    self.redo.clear();
+    self.last_edit = kind;
+  }
+
+  fn undo(&mut self) -> EditResult {
+    let Some(snapshot) = self.undo.pop() else {
+      return EditResult::Unchanged;
+    };
+    self.redo.push(self.snapshot());
+    self.restore(snapshot);
+    EditResult::Changed
+  }
+
+  fn redo(&mut self) -> EditResult {
+    let Some(snapshot) = self.redo.pop() else {
+      return EditResult::Unchanged;
+    };
+    self.undo.push(self.snapshot());
+    self.restore(snapshot);
+    EditResult::Changed
+  }
+
+  fn snapshot(&self) -> Snapshot {
+    Snapshot {
+      text: self.text.clone(),
+      cursor: self.cursor,
+    }
+  }
+
+  fn restore(&mut self, snapshot: Snapshot) {
+    self.text = snapshot.text;
+    self.cursor = snapshot.cursor;
+    self.last_edit = None;
+  }
+
+  fn previous_boundary(&self) -> usize {
+    self.text[..self.cursor]
+      .char_indices()
+      .next_back()
+      .map_or(0, |(index, _)| index)
+  }
+
+  fn next_boundary(&self) -> usize {
+    self.text[self.cursor..]
+      .chars()
+      .next()
+      .map_or(self.cursor, |ch| self.cursor + ch.len_utf8())
+  }
+
+  /// Start of the word before the cursor, skipping whitespace first.
+  fn word_start(&self) -> usize {
+    let before = self.text[..self.cursor].trim_end();
+    before
+      .char_indices()
+      .rev()
+      .find(|(_, ch)| ch.is_whitespace())
+      .map_or(0, |(index, ch)| index + ch.len_utf8())
+  }
+
+  /// End of the word after the cursor, skipping whitespace first.
+  fn word_end(&self) -> usize {
+    let after = &self.text[self.cursor..];
+    let word_offset = after.len() - after.trim_start().len();
+    after[word_offset..]
+      .char_indices()
+      .find(|(_, ch)| ch.is_whitespace())
+      .map_or(self.text.len(), |(index, _)| {
+        self.cursor + word_offset + index
+      })
+  }
+}
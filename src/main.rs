@@ -1,19 +1,8 @@
-mod app;
-mod config;
-mod config_file;
-mod filter;
-mod i18n;
-mod keymap;
-mod layout;
-mod sort;
-mod theme;
-mod ui;
-mod utils;
-
 use anyhow::{Context, Result};
+use calibre_tui::{app, config, history, keymap, layout, session, theme};
 use clap::Parser;
 use crossterm::{
-  event::{DisableMouseCapture, EnableMouseCapture},
+  event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
   execute,
   terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -25,22 +14,52 @@ use std::io;
 struct Args {
   #[arg(long)]
   exit_on_open: bool,
+  /// Start without restoring the previous session
+  #[arg(long)]
+  fresh: bool,
 }
 
 fn main() -> Result<()> {
   let args = Args::parse();
   let config = config::load_config().context("failed to load configuration")?;
-  let keymap = keymap::load_keymap().context("failed to load keymap")?;
-  let layout = layout::load_layout().context("failed to load layout")?;
+  let keymaps = keymap::load_keymap().context("failed to load keymap")?;
+  let layouts = layout::load_layout().context("failed to load layout")?;
   let theme = theme::load_theme().context("failed to load theme")?;
-  let mut app = app::App::new(config, keymap, layout, theme, args.exit_on_open)?;
+  let session = if args.fresh {
+    None
+  } else {
+    session::load_session().unwrap_or_else(|error| {
+      eprintln!("Ignoring previous session: {error:#}");
+      None
+    })
+  };
+  let history = history::load_history().unwrap_or_else(|error| {
+    eprintln!("Ignoring saved history: {error:#}");
+    history::History::default()
+  });
+  let mut app = app::App::new(
+    config,
+    keymaps,
+    layouts,
+    theme,
+    args.exit_on_open,
+    session,
+    history,
+  )?;
 
   let mut terminal = setup_terminal()?;
   let result = app.run(&mut terminal);
   restore_terminal(&mut terminal)?;
   drop(terminal);
 
-  for path in result? {
+  let paths = result?;
+  if let Err(error) = session::save_session(&app.session()) {
+    eprintln!("Failed to save session: {error:#}");
+  }
+  if let Err(error) = history::save_history(app.history()) {
+    eprintln!("Failed to save history: {error:#}");
+  }
+  for path in paths {
     println!("{}", path.display());
   }
 
@@ -51,8 +70,13 @@ fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
   enable_raw_mode().context("failed to enable raw mode")?;
 
   let mut stdout = io::stdout();
-  execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
-    .context("failed to enter alternate screen")?;
+  execute!(
+    stdout,
+    EnterAlternateScreen,
+    EnableMouseCapture,
+    EnableBracketedPaste
+  )
+  .context("failed to enter alternate screen")?;
 
   Terminal::new(CrosstermBackend::new(stdout)).context("failed to create terminal")
 }
@@ -62,7 +86,8 @@ fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Re
   execute!(
     terminal.backend_mut(),
     LeaveAlternateScreen,
-    DisableMouseCapture
+    DisableMouseCapture,
+    DisableBracketedPaste
   )
   .context("failed to leave alternate screen")?;
   terminal.show_cursor().context("failed to show cursor")?;
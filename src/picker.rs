@@ -0,0 +1,65 @@
+use crate::line_edit::LineEditor;
+
+/// Filterable popup list, used for history search, saved searches and virtual libraries.
+#[derive(Debug, Clone)]
+pub struct Picker {
+  pub title: String,
+  pub items: Vec<PickerItem>,
+  pub query: LineEditor,
+  /// Indices of items matching the query, in display order.
+  pub matches: Vec<usize>,
+  pub selected: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct PickerItem {
+  pub label: String,
+  /// Secondary text shown dimmed after the label, such as a saved query.
+  pub detail: String,
+  /// Value returned when the item is picked.
+  pub value: String,
+}
+
+impl Picker {
+  pub fn new(title: impl Into<String>, items: Vec<PickerItem>) -> Self {
+    let mut picker = Self {
+      title: title.into(),
+      items,
+      query: LineEditor::default(),
+      matches: Vec::new(),
+      selected: 0,
+    };
+    picker.refresh();
+    picker
+  }
+
+  /// Keeps items whose label or detail contains the query, ignoring case, and selects
+  /// the first one.
+  pub fn refresh(&mut self) {
+    let query = self.query.text().to_lowercase();
+    self.matches = self
+      .items
+      .iter()
+      .enumerate()
+      .filter(|(_, item)| {
+        item.label.to_lowercase().contains(&query) || item.detail.to_lowercase().contains(&query)
+      })
+      .map(|(index, _)| index)
+      .collect();
+    self.selected = 0;
+  }
+
+  pub fn previous(&mut self) {
+    self.selected = self.selected.saturating_sub(1);
+  }
+
+  pub fn next(&mut self) {
+    if self.selected + 1 < self.matches.len() {
+      self.selected += 1;
+    }
+  }
+
+  pub fn selected_item(&self) -> Option<&PickerItem> {
+    self.items.get(*self.matches.get(self.selected)?)
+  }
+}
@@ -0,0 +1,272 @@
+use crate::config_file::app_config_dir;
+use crate::filter::field_filter_term;
+use crate::layout::BookField;
+use crate::picker::{Picker, PickerItem};
+use crate::utils::db::load_calibre_preference;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Named queries saved with `:save-search` and `:save-library`, keyed by name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedSearches {
+  pub searches: BTreeMap<String, String>,
+  pub libraries: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SavedKind {
+  Search,
+  Library,
+}
+
+/// User saved searches combined with the ones imported from Calibre. User entries win
+/// on name clashes.
+#[derive(Debug, Clone, Default)]
+pub struct SearchCatalog {
+  user: SavedSearches,
+  calibre: SavedSearches,
+  /// Names of Calibre entries whose syntax has no equivalent here.
+  pub skipped: Vec<String>,
+  /// Why the Calibre entries could not be read; the catalog then has user entries only.
+  pub calibre_error: Option<String>,
+}
+
+impl SearchCatalog {
+  pub fn load(library_path: &Path) -> Result<Self> {
+    let user = load_saved_searches()?;
+    let mut catalog = Self {
+      user,
+      ..Self::default()
+    };
+    if let Err(error) = catalog.load_calibre(library_path) {
+      catalog.calibre = SavedSearches::default();
+      catalog.skipped.clear();
+      catalog.calibre_error = Some(format!("{error:#}"));
+    }
+    Ok(catalog)
+  }
+
+  fn load_calibre(&mut self, library_path: &Path) -> Result<()> {
+    for (kind, key) in [
+      (SavedKind::Search, "saved_searches"),
+      (SavedKind::Library, "virtual_libraries"),
+    ] {
+      let entries = load_calibre_preference::<BTreeMap<String, String>>(library_path, key)
+        .with_context(|| format!("failed to read Calibre {key}"))?
+        .unwrap_or_default();
+      for (name, expression) in entries {
+        match translate_calibre_query(&expression) {
+          Some(query) => {
+            self.calibre.entries_mut(kind).insert(name, query);
+          }
+          None => self.skipped.push(name),
+        }
+      }
+    }
+    Ok(())
+  }
+
+  pub fn get(&self, kind: SavedKind, name: &str) -> Option<&str> {
+    self
+      .user
+      .entries(kind)
+      .get(name)
+      .or_else(|| self.calibre.entries(kind).get(name))
+      .map(String::as_str)
+  }
+
+  /// Saved names in order, with Calibre names hidden by a user entry listed once.
+  pub fn names(&self, kind: SavedKind) -> Vec<&str> {
+    let mut names = self
+      .user
+      .entries(kind)
+      .keys()
+      .chain(self.calibre.entries(kind).keys())
+      .map(String::as_str)
+      .collect::<Vec<_>>();
+    names.sort_unstable_by_key(|name| name.to_lowercase());
+    names.dedup();
+    names
+  }
+
+  /// Saves a named query to `searches.toml`, replacing an entry with the same name.
+  pub fn save(&mut self, kind: SavedKind, name: &str, query: &str) -> Result<()> {
+    self
+      .user
+      .entries_mut(kind)
+      .insert(name.to_string(), query.to_string());
+    save_saved_searches(&self.user)
+  }
+
+  /// Removes a user entry. Returns `false` when there is none; Calibre entries are kept.
+  pub fn delete(&mut self, kind: SavedKind, name: &str) -> Result<bool> {
+    if self.user.entries_mut(kind).remove(name).is_none() {
+      return Ok(false);
+    }
+    save_saved_searches(&self.user)?;
+    Ok(true)
+  }
+
+  pub fn picker(&self, kind: SavedKind) -> Picker {
+    let mut items = Vec::new();
+    if kind == SavedKind::Library {
+      items.push(PickerItem {
+        label: "(all books)".to_string(),
+        detail: String::new(),
+        value: String::new(),
+      });
+    }
+    items.extend(self.names(kind).into_iter().map(|name| PickerItem {
+      label: name.to_string(),
+      detail: self.get(kind, name).unwrap_or_default().to_string(),
+      value: name.to_string(),
+    }));
+    let title = match kind {
+      SavedKind::Search => "Saved Searches",
+      SavedKind::Library => "Virtual Libraries",
+    };
+    Picker::new(title, items)
+  }
+}
+
+impl SavedSearches {
+  fn entries(&self, kind: SavedKind) -> &BTreeMap<String, String> {
+    match kind {
+      SavedKind::Search => &self.searches,
+      SavedKind::Library => &self.libraries,
+    }
+  }
+
+  fn entries_mut(&mut self, kind: SavedKind) -> &mut BTreeMap<String, String> {
+    match kind {
+      SavedKind::Search => &mut self.searches,
+      SavedKind::Library => &mut self.libraries,
+    }
+  }
+}
+
+/// Converts a Calibre search expression into a query. Terms joined by `and` or spaces,
+/// `field:value`, `field:"=value"` and `field:false` are supported. Returns `None` for `or`,
+/// `not`, parentheses, regular expressions, and fields without a column here.
+pub fn translate_calibre_query(expression: &str) -> Option<String> {
+  let mut terms = Vec::new();
+  for token in calibre_tokens(expression)? {
+    if token.eq_ignore_ascii_case("and") {
+      continue;
+    }
+    if token.eq_ignore_ascii_case("or")
+      || token.eq_ignore_ascii_case("not")
+      || token == "("
+      || token == ")"
+    {
+      return None;
+    }
+
+    let Some((name, value)) = token.split_once(':') else {
+      terms.push(quote_term(&token));
+      continue;
+    };
+    let field = calibre_field(name)?;
+    let value = value.trim_matches('"');
+    if value.starts_with('~') {
+      return None;
+    }
+    if value.eq_ignore_ascii_case("false") {
+      terms.push(field_filter_term(field, ""));
+    } else if value.eq_ignore_ascii_case("true") {
+      return None;
+    } else if let Some(value) = value.strip_prefix('=') {
+      // `=.` is Calibre's "this tag and its children", which exact tag filters already do.
+      let value = value.strip_prefix('.').unwrap_or(value);
+      terms.push(field_filter_term(field, value));
+    } else {
+      terms.push(format!("{}:{}", field.name(), quote_term(value)));
+    }
+  }
+  Some(terms.join(" "))
+}
+
+/// Splits at whitespace and parentheses outside double quotes, keeping quotes in tokens.
+/// Returns `None` for an unterminated quote.
+fn calibre_tokens(expression: &str) -> Option<Vec<String>> {
+  let mut tokens = Vec::new();
+  let mut current = String::new();
+  let mut quoted = false;
+  let mut escaped = false;
+  for ch in expression.chars() {
+    if escaped {
+      current.push(ch);
+      escaped = false;
+      continue;
+    }
+    match ch {
+      '\\' if quoted => escaped = true,
+      '"' => {
+        quoted = !quoted;
+        current.push(ch);
+      }
+      '(' | ')' if !quoted => {
+        tokens.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+        tokens.push(ch.to_string());
+      }
+      ch if ch.is_whitespace() && !quoted => {
+        tokens.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+      }
+      ch => current.push(ch),
+    }
+  }
+  if quoted {
+    return None;
+  }
+  tokens.extend((!current.is_empty()).then_some(current));
+  Some(tokens)
+}
+
+fn calibre_field(name: &str) -> Option<BookField> {
+  match name.to_lowercase().as_str() {
+    "title" => Some(BookField::Title),
+    "author" | "authors" => Some(BookField::Authors),
+    "series" => Some(BookField::Series),
+    "format" | "formats" => Some(BookField::Formats),
+    "tag" | "tags" => Some(BookField::Tags),
+    "publisher" => Some(BookField::Publisher),
+    "language" | "languages" => Some(BookField::Languages),
+    _ => None,
+  }
+}
+
+fn quote_term(value: &str) -> String {
+  let value = value.trim_matches('"').replace('"', "");
+  if value.contains(char::is_whitespace) {
+    format!("\"{value}\"")
+  } else {
+    value
+  }
+}
+
+fn load_saved_searches() -> Result<SavedSearches> {
+  let path = saved_searches_path()?;
+  if !path.exists() {
+    return Ok(SavedSearches::default());
+  }
+
+  let content = fs::read_to_string(&path)
+    .with_context(|| format!("failed to read saved searches file: {:?}", path))?;
+  toml::from_str(&content)
+    .with_context(|| format!("failed to parse saved searches file '{}'", path.display()))
+}
+
+fn save_saved_searches(saved: &SavedSearches) -> Result<()> {
+  let path = saved_searches_path()?;
+  fs::write(&path, toml::to_string_pretty(saved)?)
+    .with_context(|| format!("failed to write saved searches file to {:?}", path))?;
+  Ok(())
+}
+
+fn saved_searches_path() -> Result<PathBuf> {
+  Ok(app_config_dir()?.join("searches.toml"))
+}
@@ -0,0 +1,48 @@
+use crate::config_file::app_state_dir;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Browser state saved on quit and restored on the next launch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+  pub query: String,
+  /// Sort keys in `sort` command syntax.
+  pub sort: String,
+  /// Active virtual library name.
+  pub library: Option<String>,
+  /// Active layout view, when it is not the default one.
+  pub view: Option<String>,
+  /// Book list display mode, when it is not the table.
+  pub display: Option<String>,
+  /// Calibre book ids, so the state survives library changes.
+  pub focused_book_id: Option<i64>,
+  pub selected_book_ids: Vec<i64>,
+  pub scroll_offset: usize,
+}
+
+pub fn load_session() -> Result<Option<Session>> {
+  let path = session_path()?;
+  if !path.exists() {
+    return Ok(None);
+  }
+
+  let content = fs::read_to_string(&path)
+    .with_context(|| format!("failed to read session file: {:?}", path))?;
+  let session = toml::from_str(&content)
+    .with_context(|| format!("failed to parse session file '{}'", path.display()))?;
+  Ok(Some(session))
+}
+
+pub fn save_session(session: &Session) -> Result<()> {
+  let path = session_path()?;
+  fs::write(&path, toml::to_string_pretty(session)?)
+    .with_context(|| format!("failed to write session file to {:?}", path))?;
+  Ok(())
+}
+
+fn session_path() -> Result<PathBuf> {
+  Ok(app_state_dir()?.join("session.toml"))
+}
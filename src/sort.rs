@@ -1,8 +1,10 @@
 use crate::filter::SearchResult;
-use crate::layout::{BookField, Layout};
+use crate::i18n::filter::{SortReading, fold_accents, sort_reading};
+use crate::layout::{BookField, ColumnKey, Layout};
 use crate::utils::book::Book;
 use anyhow::{Result, bail};
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SortDirection {
@@ -12,36 +14,88 @@ pub enum SortDirection {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SortKey {
-  pub field: BookField,
+  pub column: ColumnKey,
   pub direction: SortDirection,
+  pub collation: Collation,
+  /// Sort by the displayed text instead of Calibre's `title_sort` / `author_sort`.
+  pub display: bool,
+}
+
+/// How field values are compared. The default compares ASCII-lowercased text byte-wise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Collation {
+  /// Compare digit runs by numeric value, so `Volume 2` comes before `Volume 10`.
+  pub natural: bool,
+  /// Lowercase every script instead of ASCII only.
+  pub case_fold: bool,
+  /// Ignore accents and compatibility forms, so `Ärger` sorts with `Arger`.
+  pub ignore_accents: bool,
+  /// Order by pronunciation before comparing.
+  pub reading: Option<SortReading>,
+}
+
+/// One comparable piece of a collated value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SortSegment {
+  /// Digits without leading zeros.
+  Number(String),
+  Text(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SortSpec {
   keys: Vec<SortKey>,
+  /// Group results by the first search field that matched before applying the keys.
+  group_by_match: bool,
 }
 
 impl Default for SortSpec {
   fn default() -> Self {
     Self {
       keys: vec![SortKey {
-        field: BookField::Title,
+        column: ColumnKey::Field(BookField::Title),
         direction: SortDirection::Asc,
+        collation: Collation::default(),
+        display: false,
       }],
+      group_by_match: true,
     }
   }
 }
 
 impl SortSpec {
   pub fn parse(args: &[&str]) -> Result<Self> {
+    Self::default().updated(args)
+  }
+
+  /// Applies `sort` command arguments to this spec. Sort keys replace the current keys,
+  /// while `relevance` / `nogroup` only switch match grouping.
+  pub fn updated(&self, args: &[&str]) -> Result<Self> {
     if args.is_empty() {
-      bail!("usage: sort <field> [asc|desc] [field] [asc|desc] ...");
+      bail!(
+        "usage: sort [relevance|nogroup] <field> [asc|desc] [option...] [field] [asc|desc] [option...] ..."
+      );
     }
 
+    let mut group_by_match = self.group_by_match;
     let mut keys = Vec::new();
     let mut index = 0;
     while index < args.len() {
-      let Some(field) = BookField::parse(args[index]) else {
+      match args[index].to_ascii_lowercase().as_str() {
+        "relevance" => {
+          group_by_match = true;
+          index += 1;
+          continue;
+        }
+        "nogroup" => {
+          group_by_match = false;
+          index += 1;
+          continue;
+        }
+        _ => {}
+      }
+
+      let Some(column) = ColumnKey::parse(args[index]) else {
         bail!("unknown sort field: {}", args[index]);
       };
       index += 1;
@@ -55,22 +109,84 @@ impl SortSpec {
         })
         .unwrap_or(SortDirection::Asc);
 
-      keys.push(SortKey { field, direction });
+      let mut collation = Collation::default();
+      let mut display = false;
+      while let Some(option) = args.get(index) {
+        if option.eq_ignore_ascii_case("display") {
+          if !matches!(
+            column,
+            ColumnKey::Field(BookField::Title | BookField::Authors)
+          ) {
+            bail!("display only applies to title and authors");
+          }
+          display = true;
+        } else if !collation.apply(option)? {
+          break;
+        }
+        index += 1;
+      }
+
+      keys.push(SortKey {
+        column,
+        direction,
+        collation,
+        display,
+      });
+    }
+
+    if keys.is_empty() {
+      keys = self.keys.clone();
     }
+    Ok(Self {
+      keys,
+      group_by_match,
+    })
+  }
+
+  pub fn primary_key(&self) -> Option<&SortKey> {
+    self.keys.first()
+  }
 
-    Ok(Self { keys })
+  /// Columns used by the sort keys, in order.
+  pub fn columns(&self) -> impl Iterator<Item = &ColumnKey> {
+    self.keys.iter().map(|key| &key.column)
   }
 
   pub fn label(&self) -> String {
-    self
+    let mut label = self
       .keys
       .iter()
-      .map(|key| format!("{} {}", key.field.name(), key.direction.name()))
+      .map(key_args)
       .collect::<Vec<_>>()
-      .join(", ")
+      .join(", ");
+    if !self.group_by_match {
+      label.push_str(", nogroup");
+    }
+    label
+  }
+
+  /// The spec in `sort` command syntax, accepted by [`SortSpec::parse`].
+  pub fn command_args(&self) -> String {
+    let mut args = self.keys.iter().map(key_args).collect::<Vec<_>>().join(" ");
+    if !self.group_by_match {
+      args.push_str(" nogroup");
+    }
+    args
   }
 }
 
+fn key_args(key: &SortKey) -> String {
+  let mut args = format!("{} {}", key.column.name(), key.direction.name());
+  if key.display {
+    args.push_str(" display");
+  }
+  for option in key.collation.options() {
+    args.push(' ');
+    args.push_str(option);
+  }
+  args
+}
+
 impl SortDirection {
   fn parse(input: &str) -> Option<Self> {
     match input.to_ascii_lowercase().as_str() {
@@ -88,6 +204,88 @@ impl SortDirection {
   }
 }
 
+impl Collation {
+  /// Applies one collation option. Returns `false` when the input is not an option.
+  fn apply(&mut self, input: &str) -> Result<bool> {
+    match input.to_ascii_lowercase().as_str() {
+      "natural" => self.natural = true,
+      "casefold" => self.case_fold = true,
+      "noaccent" => self.ignore_accents = true,
+      "pinyin" => self.set_reading(SortReading::Pinyin)?,
+      "kana" => self.set_reading(SortReading::Kana)?,
+      _ => return Ok(false),
+    }
+    Ok(true)
+  }
+
+  fn set_reading(&mut self, reading: SortReading) -> Result<()> {
+    if self.reading.is_some_and(|current| current != reading) {
+      bail!("pinyin and kana cannot be combined on one sort key");
+    }
+    self.reading = Some(reading);
+    Ok(())
+  }
+
+  fn options(self) -> Vec<&'static str> {
+    let mut options = Vec::new();
+    if self.natural {
+      options.push("natural");
+    }
+    if self.case_fold {
+      options.push("casefold");
+    }
+    if self.ignore_accents {
+      options.push("noaccent");
+    }
+    match self.reading {
+      Some(SortReading::Pinyin) => options.push("pinyin"),
+      Some(SortReading::Kana) => options.push("kana"),
+      None => {}
+    }
+    options
+  }
+
+  fn collate(self, text: &str) -> Vec<SortSegment> {
+    let mut text = match self.reading {
+      Some(reading) => sort_reading(text, reading),
+      None => text.to_string(),
+    };
+    if self.ignore_accents {
+      text = fold_accents(&text);
+    }
+    text = if self.case_fold {
+      text.to_lowercase()
+    } else {
+      text.to_ascii_lowercase()
+    };
+
+    if self.natural {
+      natural_segments(&text)
+    } else {
+      vec![SortSegment::Text(text)]
+    }
+  }
+}
+
+impl Ord for SortSegment {
+  fn cmp(&self, other: &Self) -> Ordering {
+    match (self, other) {
+      (Self::Number(left), Self::Number(right)) => {
+        left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+      }
+      (Self::Number(_), Self::Text(_)) => Ordering::Less,
+      (Self::Text(_), Self::Number(_)) => Ordering::Greater,
+      (Self::Text(left), Self::Text(right)) => left.cmp(right),
+    }
+  }
+}
+
+impl PartialOrd for SortSegment {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
 pub fn sort_results(
   results: &mut [SearchResult],
   books: &[Book],
@@ -95,42 +293,58 @@ pub fn sort_results(
   layout: &Layout,
 ) {
   let match_fields = layout.search_fields().collect::<Vec<_>>();
+  let sort_values = results
+    .iter()
+    .filter_map(|result| {
+      let book = books.get(result.book_index)?;
+      let values = spec
+        .keys
+        .iter()
+        .map(|key| key.collation.collate(&field_value(book, key, layout)))
+        .collect::<Vec<_>>();
+      Some((result.book_index, values))
+    })
+    .collect::<HashMap<_, _>>();
   results.sort_by(|left, right| {
-    compare_match_priority(left, right, &match_fields)
-      .then_with(|| compare_results(left, right, books, spec))
+    let priority = if spec.group_by_match {
+      compare_match_priority(left, right, &match_fields)
+    } else {
+      Ordering::Equal
+    };
+    priority.then_with(|| compare_results(left, right, &sort_values, spec))
   });
 }
 
 fn compare_match_priority(
   left: &SearchResult,
   right: &SearchResult,
-  match_fields: &[BookField],
+  match_fields: &[&ColumnKey],
 ) -> Ordering {
   match_priority(left, match_fields).cmp(&match_priority(right, match_fields))
 }
 
-fn match_priority(result: &SearchResult, match_fields: &[BookField]) -> usize {
+fn match_priority(result: &SearchResult, match_fields: &[&ColumnKey]) -> usize {
   match_fields
     .iter()
-    .position(|field| !result.highlights.ranges(*field).is_empty())
+    .position(|column| !result.highlights.column_ranges(column).is_empty())
     .unwrap_or(match_fields.len())
 }
 
 fn compare_results(
   left: &SearchResult,
   right: &SearchResult,
-  books: &[Book],
+  sort_values: &HashMap<usize, Vec<Vec<SortSegment>>>,
   spec: &SortSpec,
 ) -> Ordering {
-  let Some(left_book) = books.get(left.book_index) else {
+  let Some(left_values) = sort_values.get(&left.book_index) else {
     return left.book_index.cmp(&right.book_index);
   };
-  let Some(right_book) = books.get(right.book_index) else {
+  let Some(right_values) = sort_values.get(&right.book_index) else {
     return left.book_index.cmp(&right.book_index);
   };
 
-  for key in &spec.keys {
-    let ordering = field_value(left_book, key.field).cmp(&field_value(right_book, key.field));
+  for ((key, left_value), right_value) in spec.keys.iter().zip(left_values).zip(right_values) {
+    let ordering = left_value.cmp(right_value);
     let ordering = match key.direction {
       SortDirection::Asc => ordering,
       SortDirection::Desc => ordering.reverse(),
@@ -143,12 +357,56 @@ fn compare_results(
   left.book_index.cmp(&right.book_index)
 }
 
-fn field_value(book: &Book, field: BookField) -> String {
+/// Template columns missing from the layout, such as after switching views, sort as empty.
+fn field_value(book: &Book, key: &SortKey, layout: &Layout) -> String {
+  let field = match &key.column {
+    ColumnKey::Field(field) => *field,
+    ColumnKey::Template(_) => {
+      return layout
+        .column(&key.column)
+        .and_then(|column| column.template.as_ref())
+        .map(|template| template.render(book))
+        .unwrap_or_default();
+    }
+  };
   match field {
-    BookField::Title => book.title.to_ascii_lowercase(),
-    BookField::Authors => book.authors.join(" & ").to_ascii_lowercase(),
-    BookField::Series => book.series.to_ascii_lowercase(),
-    BookField::Formats => book.formats.join(", ").to_ascii_lowercase(),
-    BookField::Tags => book.tags.join(", ").to_ascii_lowercase(),
+    BookField::Title if !key.display && !book.title_sort.is_empty() => book.title_sort.clone(),
+    BookField::Title => book.title.clone(),
+    BookField::Authors if !key.display && !book.author_sort.is_empty() => book.author_sort.clone(),
+    BookField::Authors => book.authors.join(" & "),
+    BookField::Series => book.series.clone(),
+    BookField::Formats => book.formats.join(", "),
+    BookField::Tags => book.tags.join(", "),
+    BookField::Publisher => book.publisher.clone(),
+    BookField::Languages => book.languages.join(", "),
+  }
+}
+
+fn natural_segments(text: &str) -> Vec<SortSegment> {
+  let mut segments = Vec::new();
+  let mut current = String::new();
+  let mut in_number = false;
+
+  for ch in text.chars() {
+    let is_digit = ch.is_ascii_digit();
+    if is_digit != in_number && !current.is_empty() {
+      segments.push(natural_segment(std::mem::take(&mut current), in_number));
+    }
+    in_number = is_digit;
+    current.push(ch);
+  }
+  if !current.is_empty() {
+    segments.push(natural_segment(current, in_number));
+  }
+
+  segments
+}
+
+fn natural_segment(text: String, is_number: bool) -> SortSegment {
+  if is_number {
+    let digits = text.trim_start_matches('0');
+    SortSegment::Number(if digits.is_empty() { "0" } else { digits }.to_string())
+  } else {
+    SortSegment::Text(text)
   }
 }
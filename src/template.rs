@@ -0,0 +1,435 @@
+use crate::utils::book::Book;
+use anyhow::{Result, bail};
+
+/// Column text built from book fields with a subset of Calibre's template language.
+#[derive(Debug, Clone)]
+pub struct Template {
+  source: String,
+  parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone)]
+enum TemplatePart {
+  Text(String),
+  Field(FieldRef),
+}
+
+/// One `{field:format|prefix|suffix}` reference.
+#[derive(Debug, Clone)]
+struct FieldRef {
+  field: TemplateField,
+  format: FieldFormat,
+  /// Text around the value, added only when the value is not empty.
+  prefix: String,
+  suffix: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateField {
+  Title,
+  TitleSort,
+  Authors,
+  AuthorSort,
+  Series,
+  SeriesIndex,
+  Formats,
+  Tags,
+  Publisher,
+  Languages,
+  Pubdate,
+  Timestamp,
+}
+
+#[derive(Debug, Clone)]
+enum FieldFormat {
+  Plain,
+  First,
+  Last,
+  /// strftime-style pattern for date fields, such as `%Y`.
+  Date(String),
+  IfEmpty(String),
+  Test(String, String),
+}
+
+const FIELD_NAMES: &str = "title, title_sort, authors, author_sort, series, series_index, formats, tags, publisher, languages, pubdate, timestamp";
+const DATE_SPECIFIERS: &[char] = &['Y', 'y', 'm', 'd', 'H', 'M', 'S', 'b', 'B', '%'];
+const MONTHS: [&str; 12] = [
+  "January",
+  "February",
+  "March",
+  "April",
+  "May",
+  "June",
+  "July",
+  "August",
+  "September",
+  "October",
+  "November",
+  "December",
+];
+
+impl Template {
+  /// Parses a template. Text outside braces is copied as is, and `\` escapes the next
+  /// character, such as `\{` or `\,` inside function arguments.
+  pub fn parse(source: &str) -> Result<Self> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = source.chars();
+    while let Some(ch) = chars.next() {
+      match ch {
+        '\\' => match chars.next() {
+          Some(escaped) => text.push(escaped),
+          None => bail!("template ends with a lone '\\'"),
+        },
+        '{' => {
+          let mut inner = Vec::new();
+          loop {
+            match chars.next() {
+              Some('\\') => match chars.next() {
+                Some(escaped) => inner.push((escaped, true)),
+                None => bail!("template ends with a lone '\\'"),
+              },
+              Some('}') => break,
+              Some('{') => bail!("nested '{{' inside a field reference"),
+              Some(ch) => inner.push((ch, false)),
+              None => bail!("missing '}}' after '{{'"),
+            }
+          }
+          if !text.is_empty() {
+            parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+          }
+          parts.push(TemplatePart::Field(FieldRef::parse(&inner)?));
+        }
+        '}' => bail!("'}}' without a matching '{{'"),
+        ch => text.push(ch),
+      }
+    }
+    if !text.is_empty() {
+      parts.push(TemplatePart::Text(text));
+    }
+    if !parts
+      .iter()
+      .any(|part| matches!(part, TemplatePart::Field(_)))
+    {
+      bail!("template does not reference any field");
+    }
+
+    Ok(Self {
+      source: source.to_string(),
+      parts,
+    })
+  }
+
+  pub fn source(&self) -> &str {
+    &self.source
+  }
+
+  pub fn render(&self, book: &Book) -> String {
+    self
+      .parts
+      .iter()
+      .map(|part| match part {
+        TemplatePart::Text(text) => text.clone(),
+        TemplatePart::Field(field) => field.render(book),
+      })
+      .collect()
+  }
+}
+
+impl FieldRef {
+  fn parse(inner: &[(char, bool)]) -> Result<Self> {
+    let pieces = split_unescaped(inner, ':', 2);
+    let name = unescape(pieces[0]).trim().to_string();
+    let Some(field) = TemplateField::parse(&name) else {
+      bail!("unknown template field '{name}'; expected one of {FIELD_NAMES}");
+    };
+
+    let (format, prefix, suffix) = match pieces.get(1) {
+      None => (FieldFormat::Plain, String::new(), String::new()),
+      Some(spec) => {
+        let spec = split_unescaped(spec, '|', usize::MAX);
+        match spec.as_slice() {
+          [format] => (
+            FieldFormat::parse(field, format)?,
+            String::new(),
+            String::new(),
+          ),
+          [format, prefix, suffix] => (
+            FieldFormat::parse(field, format)?,
+            unescape(prefix),
+            unescape(suffix),
+          ),
+          _ => bail!("expected {{{name}:format|prefix|suffix}}, with both prefix and suffix"),
+        }
+      }
+    };
+
+    Ok(Self {
+      field,
+      format,
+      prefix,
+      suffix,
+    })
+  }
+
+  fn render(&self, book: &Book) -> String {
+    let value = match &self.format {
+      FieldFormat::Plain => self.field.text(book),
+      FieldFormat::First => self.field.values(book).first().cloned().unwrap_or_default(),
+      FieldFormat::Last => self.field.values(book).last().cloned().unwrap_or_default(),
+      FieldFormat::Date(pattern) => self
+        .field
+        .date(book)
+        .map(|date| date.format(pattern))
+        .unwrap_or_default(),
+      FieldFormat::IfEmpty(text) => {
+        let value = self.field.text(book);
+        if value.is_empty() {
+          text.clone()
+        } else {
+          value
+        }
+      }
+      FieldFormat::Test(yes, no) => {
+        if self.field.text(book).is_empty() {
+          no.clone()
+        } else {
+          yes.clone()
+        }
+      }
+    };
+    if value.is_empty() {
+      value
+    } else {
+      format!("{}{value}{}", self.prefix, self.suffix)
+    }
+  }
+}
+
+impl FieldFormat {
+  fn parse(field: TemplateField, format: &[(char, bool)]) -> Result<Self> {
+    let text = unescape(format);
+    let name = field.name();
+    if let Some((function, args)) = function_call(format) {
+      let args = split_unescaped(args, ',', usize::MAX)
+        .into_iter()
+        .map(unescape)
+        .collect::<Vec<_>>();
+      return match (function.as_str(), args.as_slice()) {
+        ("ifempty", [text]) => Ok(Self::IfEmpty(text.clone())),
+        ("test", [yes, no]) => Ok(Self::Test(yes.clone(), no.clone())),
+        ("ifempty", _) => bail!("ifempty takes one argument in {{{name}:{text}}}"),
+        ("test", _) => bail!("test takes two arguments in {{{name}:{text}}}"),
+        _ => bail!("unknown template function '{function}' in {{{name}:{text}}}"),
+      };
+    }
+
+    match text.trim() {
+      "" => Ok(Self::Plain),
+      "first" | "last" if !field.is_list() => {
+        bail!("{text} only applies to authors, formats, tags and languages, not {name}")
+      }
+      "first" => Ok(Self::First),
+      "last" => Ok(Self::Last),
+      pattern if pattern.contains('%') => {
+        if !field.is_date() {
+          bail!("date pattern '{pattern}' only applies to pubdate and timestamp, not {name}");
+        }
+        let mut chars = pattern.chars();
+        while let Some(ch) = chars.next() {
+          if ch == '%'
+            && !chars
+              .next()
+              .is_some_and(|spec| DATE_SPECIFIERS.contains(&spec))
+          {
+            bail!("unsupported date pattern '{pattern}'; use %Y, %y, %m, %d, %H, %M, %S, %b or %B");
+          }
+        }
+        Ok(Self::Date(pattern.to_string()))
+      }
+      other => bail!(
+        "unknown format '{other}' in {{{name}:{other}}}; expected first, last, ifempty(text), test(yes,no) or a date pattern such as %Y"
+      ),
+    }
+  }
+}
+
+impl TemplateField {
+  fn parse(name: &str) -> Option<Self> {
+    match name.to_ascii_lowercase().as_str() {
+      "title" => Some(Self::Title),
+      "title_sort" | "sort" => Some(Self::TitleSort),
+      "authors" | "author" => Some(Self::Authors),
+      "author_sort" => Some(Self::AuthorSort),
+      "series" => Some(Self::Series),
+      "series_index" => Some(Self::SeriesIndex),
+      "formats" | "format" => Some(Self::Formats),
+      "tags" | "tag" => Some(Self::Tags),
+      "publisher" => Some(Self::Publisher),
+      "languages" | "language" => Some(Self::Languages),
+      "pubdate" => Some(Self::Pubdate),
+      "timestamp" | "added" => Some(Self::Timestamp),
+      _ => None,
+    }
+  }
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::Title => "title",
+      Self::TitleSort => "title_sort",
+      Self::Authors => "authors",
+      Self::AuthorSort => "author_sort",
+      Self::Series => "series",
+      Self::SeriesIndex => "series_index",
+      Self::Formats => "formats",
+      Self::Tags => "tags",
+      Self::Publisher => "publisher",
+      Self::Languages => "languages",
+      Self::Pubdate => "pubdate",
+      Self::Timestamp => "timestamp",
+    }
+  }
+
+  fn is_list(self) -> bool {
+    matches!(
+      self,
+      Self::Authors | Self::Formats | Self::Tags | Self::Languages
+    )
+  }
+
+  fn is_date(self) -> bool {
+    matches!(self, Self::Pubdate | Self::Timestamp)
+  }
+
+  fn values(self, book: &Book) -> Vec<String> {
+    match self {
+      Self::Authors => book.authors.clone(),
+      Self::Formats => book.formats.clone(),
+      Self::Tags => book.tags.clone(),
+      Self::Languages => book.languages.clone(),
+      _ => vec![self.text(book)],
+    }
+  }
+
+  /// The value as displayed in a plain `{field}` reference.
+  fn text(self, book: &Book) -> String {
+    match self {
+      Self::Title => book.title.clone(),
+      Self::TitleSort => book.title_sort.clone(),
+      Self::Authors => book.authors.join(" & "),
+      Self::AuthorSort => book.author_sort.clone(),
+      Self::Series => book.series.clone(),
+      // Calibre keeps an index for books without a series; it means nothing there.
+      Self::SeriesIndex if book.series.is_empty() => String::new(),
+      Self::SeriesIndex => format_series_index(book.series_index),
+      Self::Formats => book.formats.join(", "),
+      Self::Tags => book.tags.join(", "),
+      Self::Publisher => book.publisher.clone(),
+      Self::Languages => book.languages.join(", "),
+      Self::Pubdate | Self::Timestamp => self
+        .date(book)
+        .map(|date| date.format("%Y-%m-%d"))
+        .unwrap_or_default(),
+    }
+  }
+
+  fn date(self, book: &Book) -> Option<Date> {
+    match self {
+      Self::Pubdate => Date::parse(&book.pubdate),
+      Self::Timestamp => Date::parse(&book.timestamp),
+      _ => None,
+    }
+  }
+}
+
+/// Date and time parts of a Calibre timestamp such as `2019-05-01 12:00:00+00:00`.
+struct Date {
+  year: u32,
+  month: u32,
+  day: u32,
+  hour: u32,
+  minute: u32,
+  second: u32,
+}
+
+impl Date {
+  /// Returns `None` for empty values and Calibre's "undefined" date in year 101.
+  fn parse(text: &str) -> Option<Self> {
+    let number = |range: std::ops::Range<usize>| text.get(range)?.parse::<u32>().ok();
+    let date = Self {
+      year: number(0..4)?,
+      month: number(5..7)?,
+      day: number(8..10)?,
+      hour: number(11..13).unwrap_or(0),
+      minute: number(14..16).unwrap_or(0),
+      second: number(17..19).unwrap_or(0),
+    };
+    (date.year > 101 && (1..=12).contains(&date.month)).then_some(date)
+  }
+
+  fn format(&self, pattern: &str) -> String {
+    let mut out = String::new();
+    let mut chars = pattern.chars();
+    while let Some(ch) = chars.next() {
+      if ch != '%' {
+        out.push(ch);
+        continue;
+      }
+      let month = MONTHS[self.month as usize - 1];
+      match chars.next() {
+        Some('Y') => out.push_str(&format!("{:04}", self.year)),
+        Some('y') => out.push_str(&format!("{:02}", self.year % 100)),
+        Some('m') => out.push_str(&format!("{:02}", self.month)),
+        Some('d') => out.push_str(&format!("{:02}", self.day)),
+        Some('H') => out.push_str(&format!("{:02}", self.hour)),
+        Some('M') => out.push_str(&format!("{:02}", self.minute)),
+        Some('S') => out.push_str(&format!("{:02}", self.second)),
+        Some('b') => out.push_str(&month[..3]),
+        Some('B') => out.push_str(month),
+        Some(other) => out.push(other),
+        None => {}
+      }
+    }
+    out
+  }
+}
+
+/// Series index without a trailing `.0`, as Calibre shows it.
+pub fn format_series_index(index: f64) -> String {
+  if index.fract() == 0.0 {
+    format!("{index:.0}")
+  } else {
+    index.to_string()
+  }
+}
+
+/// `name(args)` with the arguments left escaped.
+fn function_call(format: &[(char, bool)]) -> Option<(String, &[(char, bool)])> {
+  let open = format
+    .iter()
+    .position(|&(ch, escaped)| ch == '(' && !escaped)?;
+  let (last, escaped) = *format.last()?;
+  if last != ')' || escaped {
+    return None;
+  }
+  let name = unescape(&format[..open]).trim().to_string();
+  Some((name, &format[open + 1..format.len() - 1]))
+}
+
+/// Splits at unescaped `separator`s, into at most `limit` pieces.
+fn split_unescaped(chars: &[(char, bool)], separator: char, limit: usize) -> Vec<&[(char, bool)]> {
+  let mut pieces = Vec::new();
+  let mut start = 0;
+  for (index, &(ch, escaped)) in chars.iter().enumerate() {
+    if ch == separator && !escaped && pieces.len() + 1 < limit {
+      pieces.push(&chars[start..index]);
+      start = index + 1;
+    }
+  }
+  pieces.push(&chars[start..]);
+  pieces
+}
+
+fn unescape(chars: &[(char, bool)]) -> String {
+  chars.iter().map(|(ch, _)| ch).collect()
+}
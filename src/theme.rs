@@ -14,6 +14,7 @@ pub struct Theme {
   pub search: SearchTheme,
   pub command: CommandTheme,
   pub table: TableTheme,
+  pub facets: FacetTheme,
   pub row: RowTheme,
   pub highlight: HighlightTheme,
   pub footer: FooterTheme,
@@ -48,11 +49,28 @@ pub struct TableTheme {
   pub border: String,
   pub title: String,
   pub header: String,
+  pub group_header: String,
   pub title_field: String,
   pub authors_field: String,
   pub series_field: String,
   pub formats_field: String,
   pub tags_field: String,
+  pub publisher_field: String,
+  pub languages_field: String,
+  pub template_field: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(default)]
+pub struct FacetTheme {
+  pub border: String,
+  pub focused_border: String,
+  pub title: String,
+  pub category: String,
+  pub value: String,
+  pub count: String,
+  pub active: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +100,7 @@ pub struct HighlightTheme {
 #[serde(default)]
 pub struct FooterTheme {
   pub message: String,
+  pub mode: String,
   pub which_key_background: String,
   pub which_key_foreground: String,
   pub which_key_key: String,
@@ -122,6 +141,7 @@ impl Default for Theme {
       search: SearchTheme::default(),
       command: CommandTheme::default(),
       table: TableTheme::default(),
+      facets: FacetTheme::default(),
       row: RowTheme::default(),
       highlight: HighlightTheme::default(),
       footer: FooterTheme::default(),
@@ -159,11 +179,29 @@ impl Default for TableTheme {
       border: "blue".to_string(),
       title: "blue".to_string(),
       header: "blue".to_string(),
+      group_header: "yellow".to_string(),
       title_field: "white".to_string(),
       authors_field: "cyan".to_string(),
       series_field: "white".to_string(),
       formats_field: "magenta".to_string(),
       tags_field: "cyan".to_string(),
+      publisher_field: "white".to_string(),
+      languages_field: "magenta".to_string(),
+      template_field: "white".to_string(),
+    }
+  }
+}
+
+impl Default for FacetTheme {
+  fn default() -> Self {
+    Self {
+      border: "dark_gray".to_string(),
+      focused_border: "blue".to_string(),
+      title: "blue".to_string(),
+      category: "yellow".to_string(),
+      value: "white".to_string(),
+      count: "dark_gray".to_string(),
+      active: "green".to_string(),
     }
   }
 }
@@ -196,6 +234,7 @@ impl Default for FooterTheme {
   fn default() -> Self {
     Self {
       message: "dark_gray".to_string(),
+      mode: "yellow".to_string(),
       which_key_background: "reset".to_string(),
       which_key_foreground: "white".to_string(),
       which_key_key: "yellow".to_string(),
@@ -298,10 +337,42 @@ impl CommentedToml for Theme {
         path: "table.header",
         lines: &["Table header color."],
       },
+      TomlComment {
+        path: "table.group_header",
+        lines: &["Group header row color used by the group command."],
+      },
       TomlComment {
         path: "table.title_field",
         lines: &["Per-field text colors for visible book columns."],
       },
+      TomlComment {
+        path: "table.template_field",
+        lines: &["Text color of template columns from layout.toml."],
+      },
+      TomlComment {
+        path: "facets",
+        lines: &["Facet side panel colors."],
+      },
+      TomlComment {
+        path: "facets.focused_border",
+        lines: &["Border color while the panel has keyboard focus."],
+      },
+      TomlComment {
+        path: "facets.category",
+        lines: &["Field category row color."],
+      },
+      TomlComment {
+        path: "facets.value",
+        lines: &["Field value row color."],
+      },
+      TomlComment {
+        path: "facets.count",
+        lines: &["Result count color."],
+      },
+      TomlComment {
+        path: "facets.active",
+        lines: &["Color of values already used as filters in the search query."],
+      },
       TomlComment {
         path: "row",
         lines: &["Row state colors for hover, selection, and selected-hover."],
@@ -314,6 +385,10 @@ impl CommentedToml for Theme {
         path: "footer",
         lines: &["Footer message and which-key hint colors."],
       },
+      TomlComment {
+        path: "footer.mode",
+        lines: &["Modal navigation mode indicator color, used when keymap.toml sets modal = true."],
+      },
       TomlComment {
         path: "footer.which_key_separator_text",
         lines: &["Text placed between a which-key key and its description."],
@@ -336,7 +411,7 @@ impl CommentedToml for Theme {
       },
       TomlComment {
         path: "help",
-        lines: &["F1 help popup colors."],
+        lines: &["F1 help and picker popup colors."],
       },
     ]
   }
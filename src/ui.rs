@@ -1,37 +1,138 @@
+use crate::cover::CoverCache;
+use crate::facet::{FacetPanel, FacetRow};
 use crate::filter::{BookHighlights, HighlightRanges, SearchResult};
-use crate::layout::{BookField, Layout};
+use crate::group::{ResultGroup, TableRow, TableRows};
+use crate::layout::{BookField, ColumnAlign, ColumnKey, Ellipsis, Layout, LayoutColumn};
+use crate::line_edit::LineEditor;
+use crate::picker::Picker;
+use crate::template::format_series_index;
 use crate::theme::Theme;
-use crate::utils::book::Book;
+use crate::utils::book::{Book, tag_leaf};
 use framework_tui::{
   CommandCompletion, CompletionListStyle, KeyHelpDialogStyle, KeyHint, KeyHintsStyle, Prompt,
   PromptLineStyle, completion_rows, default_completion_selected_style, draw_completion_list,
   draw_key_help_dialog, draw_key_hints, draw_prompt_line, key_hint_columns, key_hint_rows,
 };
-use ratatui::layout::Rect;
+use ratatui::layout::{Flex, Position, Rect};
 use ratatui::{
   Frame,
   layout::{Constraint, Direction, Layout as TuiLayout},
   style::{Color, Modifier, Style},
-  text::{Line, Span},
-  widgets::{Block, BorderType, Borders, Cell, Paragraph, Row, Table, TableState},
+  text::{Line, Span, Text},
+  widgets::{
+    Block, BorderType, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table,
+    TableState,
+  },
 };
 use std::collections::BTreeSet;
-use unicode_width::UnicodeWidthStr;
+use std::ops::Range;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Rows built beyond each edge of the visible part of the book list.
+const WINDOW_MARGIN: usize = 16;
+/// Lines per entry in the card display.
+const CARD_HEIGHT: u16 = 2;
+/// Grid tile size, including its border.
+const TILE_HEIGHT: u16 = 4;
+const TILE_MIN_WIDTH: u16 = 24;
+/// Lines added to grid tiles for the cover thumbnail.
+const COVER_HEIGHT: u16 = 8;
+
+/// How the book list is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisplayMode {
+  #[default]
+  Table,
+  Card,
+  Grid,
+}
 
 pub struct DrawState<'a> {
-  pub input: &'a str,
+  pub input: &'a LineEditor,
   pub books: &'a [Book],
   pub results: &'a [SearchResult],
+  pub rows: &'a TableRows,
+  pub facets: &'a mut FacetPanel,
   pub table_state: &'a mut TableState,
   pub selected_book_indices: &'a BTreeSet<usize>,
   pub layout: &'a Layout,
+  pub display: DisplayMode,
+  /// Cover thumbnails for grid tiles, when `display.covers` is on.
+  pub covers: Option<&'a mut CoverCache>,
   pub theme: &'a Theme,
   pub prompt: Option<&'a Prompt>,
   pub command_completion: Option<&'a CommandCompletion>,
   pub key_hints: &'a [KeyHint],
   pub key_help_entries: Option<&'a [framework_tui::KeyHelpEntry]>,
+  /// Help dialog title, naming the mode whose bindings are listed.
+  pub key_help_title: &'a str,
   pub message: Option<&'a str>,
   pub sort_label: &'a str,
+  /// Modal navigation mode shown in the footer, with any pending count.
+  pub mode_label: Option<&'a str>,
+  pub picker: Option<&'a Picker>,
+  /// Active layout view other than the default one, shown next to the sort label.
+  pub view_label: Option<&'a str>,
+  /// Active virtual library, shown next to the sort label.
+  pub library_label: Option<&'a str>,
+  /// Filled with the table layout for mouse hit-testing.
+  pub table_geometry: &'a mut TableGeometry,
+}
+
+/// Book table layout from the last draw, used to map mouse positions to rows and columns.
+#[derive(Debug, Clone, Default)]
+pub struct TableGeometry {
+  pub header_y: u16,
+  /// Area of the table rows below the header.
+  pub body: Rect,
+  /// Index of the first row shown in `body`.
+  pub offset: usize,
+  /// Horizontal spans of the visible layout columns, as (x, width). Empty without a header.
+  pub columns: Vec<(u16, u16)>,
+  /// Screen lines per row: one in the table, more for cards and grid tiles.
+  pub row_height: u16,
+  /// Rows shown side by side, more than one in the grid.
+  pub grid_columns: usize,
+  pub tile_width: u16,
+}
+
+impl TableGeometry {
+  /// Table row index at a screen position. The row may be past the last result.
+  pub fn row_at(&self, x: u16, y: u16) -> Option<usize> {
+    if !self.body.contains(Position::new(x, y)) {
+      return None;
+    }
+    let line = usize::from((y - self.body.y) / self.row_height.max(1));
+    let column = usize::from((x - self.body.x) / self.tile_width.max(1));
+    (column < self.grid_columns).then(|| self.offset + line * self.grid_columns + column)
+  }
+
+  /// Rows that fit in the body, counting every tile of the grid.
+  pub fn page_size(&self) -> usize {
+    (usize::from(self.body.height / self.row_height.max(1)) * self.grid_columns).max(1)
+  }
+
+  /// Visible column index under a header cell.
+  pub fn header_at(&self, x: u16, y: u16) -> Option<usize> {
+    if y != self.header_y {
+      return None;
+    }
+    self
+      .columns
+      .iter()
+      .position(|(start, width)| x >= *start && x < start + width)
+  }
+
+  /// Index of the column left of a header border, when `x` is on or next to it.
+  pub fn border_at(&self, x: u16, y: u16) -> Option<usize> {
+    if y != self.header_y {
+      return None;
+    }
+    self
+      .columns
+      .windows(2)
+      .position(|pair| x + 1 == pair[1].0 || x == pair[1].0)
+  }
 }
 
 pub fn draw(frame: &mut Frame, area: Rect, state: DrawState<'_>) {
@@ -39,16 +140,26 @@ pub fn draw(frame: &mut Frame, area: Rect, state: DrawState<'_>) {
     input,
     books,
     results,
+    rows,
+    facets,
     table_state,
     selected_book_indices,
     layout,
+    display,
+    covers,
     theme,
     prompt,
     command_completion,
     key_hints,
     key_help_entries,
+    key_help_title,
     message,
     sort_label,
+    mode_label,
+    picker,
+    view_label,
+    library_label,
+    table_geometry,
   } = state;
 
   frame.render_widget(
@@ -66,7 +177,7 @@ pub fn draw(frame: &mut Frame, area: Rect, state: DrawState<'_>) {
     let columns = key_hint_columns(theme.footer.which_key_columns, area.width);
     key_hint_rows(key_hints.len(), columns)
   };
-  let footer_height = hint_rows.max(u16::from(message.is_some()));
+  let footer_height = hint_rows.max(u16::from(message.is_some() || mode_label.is_some()));
   let completion_height = if prompt.is_some() {
     completion_rows(command_completion, 5)
   } else {
@@ -88,46 +199,65 @@ pub fn draw(frame: &mut Frame, area: Rect, state: DrawState<'_>) {
     frame,
     chunks[0],
     input,
-    selected_book_indices.len(),
+    &search_title(
+      selected_book_indices.len(),
+      sort_label,
+      view_label,
+      library_label,
+    ),
     theme,
     prompt,
     command_completion,
-    sort_label,
   );
-  draw_table(
-    frame,
-    chunks[2],
+  let table_area = if facets.visible {
+    let panel_width = (chunks[2].width / 3).clamp(20, 40).min(chunks[2].width / 2);
+    let columns = TuiLayout::default()
+      .direction(Direction::Horizontal)
+      .constraints([Constraint::Length(panel_width), Constraint::Min(0)])
+      .split(chunks[2]);
+    draw_facets(frame, columns[0], facets, theme);
+    columns[1]
+  } else {
+    chunks[2]
+  };
+  let list = BookList {
     books,
     results,
-    table_state,
+    rows,
     selected_book_indices,
     layout,
     theme,
-  );
+  };
+  *table_geometry = match display {
+    DisplayMode::Table => draw_table(frame, table_area, &list, table_state),
+    DisplayMode::Card => draw_cards(frame, table_area, &list, table_state),
+    DisplayMode::Grid => draw_grid(frame, table_area, &list, table_state, covers),
+  };
   draw_command_completion(frame, chunks[1], command_completion, theme);
-  draw_footer(frame, chunks[3], key_hints, message, theme);
+  draw_footer(frame, chunks[3], key_hints, message, mode_label, theme);
 
+  if let Some(picker) = picker {
+    draw_picker(frame, area, picker, theme);
+  }
   if let Some(entries) = key_help_entries {
-    draw_key_help(frame, area, entries, theme);
+    draw_key_help(frame, area, key_help_title, entries, theme);
   }
 }
 
 fn draw_input_box(
   frame: &mut Frame,
   area: Rect,
-  input: &str,
-  selected_count: usize,
+  input: &LineEditor,
+  title: &str,
   theme: &Theme,
   prompt: Option<&Prompt>,
   command_completion: Option<&CommandCompletion>,
-  sort_label: &str,
 ) {
   if let Some(prompt) = prompt {
     draw_command_input(frame, area, prompt, command_completion, theme);
     return;
   }
 
-  let title = format!(" Search [{selected_count} selected] [sort: {sort_label}] ");
   let block = Block::default()
     .borders(Borders::ALL)
     .border_style(Style::default().fg(theme.color(&theme.search.border)))
@@ -136,18 +266,38 @@ fn draw_input_box(
       title,
       Style::default().fg(theme.color(&theme.search.title)),
     ));
-  let input_box = Paragraph::new(input)
+  // Scroll horizontally so the cursor stays inside the box.
+  let max_cursor_width = area.width.saturating_sub(3) as usize;
+  let cursor_width = input.text()[..input.cursor()].width();
+  let scroll = cursor_width.saturating_sub(max_cursor_width);
+  let input_box = Paragraph::new(input.text())
     .style(
       Style::default()
         .fg(theme.color(&theme.search.text))
         .bg(theme.color(&theme.background)),
     )
+    .scroll((0, u16::try_from(scroll).unwrap_or(u16::MAX)))
     .block(block);
   frame.render_widget(input_box, area);
 
-  let max_cursor_width = area.width.saturating_sub(2) as usize;
-  let cursor_width = input.width().min(max_cursor_width);
-  frame.set_cursor_position((area.x + cursor_width as u16 + 1, area.y + 1));
+  let cursor_x = (cursor_width - scroll).min(max_cursor_width);
+  frame.set_cursor_position((area.x + cursor_x as u16 + 1, area.y + 1));
+}
+
+fn search_title(
+  selected_count: usize,
+  sort_label: &str,
+  view_label: Option<&str>,
+  library_label: Option<&str>,
+) -> String {
+  let mut title = format!(" Search [{selected_count} selected] [sort: {sort_label}] ");
+  if let Some(view) = view_label {
+    title.push_str(&format!("[view: {view}] "));
+  }
+  if let Some(library) = library_label {
+    title.push_str(&format!("[library: {library}] "));
+  }
+  title
 }
 
 fn draw_command_input(
@@ -187,6 +337,7 @@ fn draw_footer(
   area: Rect,
   key_hints: &[KeyHint],
   message: Option<&str>,
+  mode_label: Option<&str>,
   theme: &Theme,
 ) {
   if area.height == 0 {
@@ -211,13 +362,24 @@ fn draw_footer(
     return;
   }
 
+  let mut spans = Vec::new();
+  if let Some(mode_label) = mode_label {
+    spans.push(Span::styled(
+      format!("-- {mode_label} -- "),
+      Style::default()
+        .fg(theme.color(&theme.footer.mode))
+        .add_modifier(Modifier::BOLD),
+    ));
+  }
   if let Some(message) = message {
+    spans.push(Span::styled(
+      message.to_string(),
+      Style::default().fg(theme.color(&theme.footer.message)),
+    ));
+  }
+  if !spans.is_empty() {
     frame.render_widget(
-      Paragraph::new(message.to_string()).style(
-        Style::default()
-          .fg(theme.color(&theme.footer.message))
-          .bg(theme.color(&theme.background)),
-      ),
+      Paragraph::new(Line::from(spans)).style(Style::default().bg(theme.color(&theme.background))),
       area,
     );
   }
@@ -246,6 +408,7 @@ fn draw_command_completion(
 fn draw_key_help(
   frame: &mut Frame,
   area: Rect,
+  title: &str,
   entries: &[framework_tui::KeyHelpEntry],
   theme: &Theme,
 ) {
@@ -269,22 +432,236 @@ fn draw_key_help(
     },
     ..KeyHelpDialogStyle::default()
   };
-  let _ = draw_key_help_dialog(frame, area, "Key Bindings", entries, &style);
+  let _ = draw_key_help_dialog(frame, area, title, entries, &style);
+}
+
+fn draw_picker(frame: &mut Frame, area: Rect, picker: &Picker, theme: &Theme) {
+  let width = area.width.saturating_sub(4).min(72);
+  let height = area.height.saturating_sub(4).min(16);
+  if width < 10 || height < 4 {
+    return;
+  }
+  let popup = Rect::new(
+    area.x + (area.width - width) / 2,
+    area.y + (area.height - height) / 2,
+    width,
+    height,
+  );
+  let base = Style::default()
+    .fg(theme.color(&theme.help.description))
+    .bg(theme.color(&theme.help.background));
+  let muted = base.fg(theme.color(&theme.help.muted));
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .border_style(Style::default().fg(theme.color(&theme.help.border)))
+    .border_type(BorderType::Rounded)
+    .title(Span::styled(
+      format!(" {} ", picker.title),
+      Style::default().fg(theme.color(&theme.help.key)),
+    ))
+    .style(base);
+  let inner = block.inner(popup);
+  frame.render_widget(Clear, popup);
+  frame.render_widget(block, popup);
+
+  let chunks = TuiLayout::default()
+    .direction(Direction::Vertical)
+    .constraints([Constraint::Length(1), Constraint::Min(0)])
+    .split(inner);
+  let prefix = "filter: ";
+  let query = picker.query.text();
+  frame.render_widget(
+    Paragraph::new(Line::from(vec![
+      Span::styled(
+        prefix,
+        Style::default()
+          .fg(theme.color(&theme.help.key))
+          .add_modifier(Modifier::BOLD),
+      ),
+      Span::styled(query.to_string(), base),
+    ]))
+    .style(base),
+    chunks[0],
+  );
+  let cursor_x = (prefix.width() + query[..picker.query.cursor()].width())
+    .min(usize::from(chunks[0].width.saturating_sub(1)));
+  frame.set_cursor_position((chunks[0].x + cursor_x as u16, chunks[0].y));
+
+  if picker.matches.is_empty() {
+    frame.render_widget(Paragraph::new("(no matches)").style(muted), chunks[1]);
+    return;
+  }
+  let items = picker
+    .matches
+    .iter()
+    .filter_map(|index| picker.items.get(*index))
+    .map(|item| {
+      let mut spans = vec![Span::raw(item.label.clone())];
+      if !item.detail.is_empty() {
+        spans.push(Span::styled(format!("  {}", item.detail), muted));
+      }
+      ListItem::new(Line::from(spans))
+    })
+    .collect::<Vec<_>>();
+  let list = List::new(items).style(base).highlight_style(
+    Style::default()
+      .fg(theme.color(&theme.completion.selected_foreground))
+      .bg(theme.color(&theme.completion.selected_background)),
+  );
+  let mut list_state = ListState::default().with_selected(Some(picker.selected));
+  frame.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+fn draw_facets(frame: &mut Frame, area: Rect, facets: &mut FacetPanel, theme: &Theme) {
+  let border = if facets.focused {
+    &theme.facets.focused_border
+  } else {
+    &theme.facets.border
+  };
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .border_style(Style::default().fg(theme.color(border)))
+    .border_type(BorderType::Rounded)
+    .title(Span::styled(
+      " Browse ",
+      Style::default()
+        .fg(theme.color(&theme.facets.title))
+        .add_modifier(Modifier::BOLD),
+    ));
+
+  let count_style = Style::default().fg(theme.color(&theme.facets.count));
+  let items = facets.rows.iter().map(|row| match *row {
+    FacetRow::Category(category_index) => {
+      let category = &facets.categories[category_index];
+      let marker = if category.expanded { "▾" } else { "▸" };
+      ListItem::new(Line::from(vec![
+        Span::styled(
+          format!("{marker} {}", category_label(category.field)),
+          Style::default()
+            .fg(theme.color(&theme.facets.category))
+            .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!(" ({})", category.values.len()), count_style),
+      ]))
+    }
+    FacetRow::Value(category_index, value_index) => {
+      let value = &facets.categories[category_index].values[value_index];
+      let (marker, value_style) = if value.active {
+        (
+          "✓ ",
+          Style::default()
+            .fg(theme.color(&theme.facets.active))
+            .add_modifier(Modifier::BOLD),
+        )
+      } else {
+        ("", Style::default().fg(theme.color(&theme.facets.value)))
+      };
+      let label = if value.value.is_empty() {
+        "(none)"
+      } else if value.depth > 0 {
+        tag_leaf(&value.value)
+      } else {
+        value.value.as_str()
+      };
+      let expander = match (value.has_children, value.expanded) {
+        (false, _) => " ",
+        (true, true) => "▾",
+        (true, false) => "▸",
+      };
+      let indent = "  ".repeat(value.depth + 1);
+      ListItem::new(Line::from(vec![
+        Span::styled(format!("{indent}{expander} {marker}{label}"), value_style),
+        Span::styled(format!(" ({})", value.count), count_style),
+      ]))
+    }
+  });
+
+  let highlight_style = if facets.focused {
+    Style::default()
+      .fg(theme.color(&theme.row.hover_foreground))
+      .bg(theme.color(&theme.row.hover_background))
+  } else {
+    Style::default()
+  };
+  let list = List::new(items.collect::<Vec<_>>())
+    .block(block)
+    .style(Style::default().bg(theme.color(&theme.background)))
+    .highlight_style(highlight_style);
+  frame.render_stateful_widget(list, area, &mut facets.list_state);
+}
+
+fn category_label(field: BookField) -> &'static str {
+  match field {
+    BookField::Title => "Title",
+    BookField::Authors => "Authors",
+    BookField::Series => "Series",
+    BookField::Formats => "Formats",
+    BookField::Tags => "Tags",
+    BookField::Publisher => "Publisher",
+    BookField::Languages => "Languages",
+  }
+}
+
+/// Book list data shared by the table, card, and grid displays.
+struct BookList<'a> {
+  books: &'a [Book],
+  results: &'a [SearchResult],
+  rows: &'a TableRows,
+  selected_book_indices: &'a BTreeSet<usize>,
+  layout: &'a Layout,
+  theme: &'a Theme,
+}
+
+impl BookList<'_> {
+  /// The framed book list area, and the area inside its border.
+  fn frame_block(&self, area: Rect) -> (Block<'static>, Rect) {
+    let theme = self.theme;
+    let title = match self.rows.group_field {
+      Some(field) => format!(" Book List [group: {}] ", field.name()),
+      None => " Book List ".to_string(),
+    };
+    let block = Block::default()
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(theme.color(&theme.table.border)))
+      .border_type(BorderType::Rounded)
+      .title(Span::styled(
+        title,
+        Style::default().fg(theme.color(&theme.table.title)),
+      ));
+    let inner = block.inner(area);
+    (block, inner)
+  }
+
+  fn is_marked(&self, result: &SearchResult) -> bool {
+    self.selected_book_indices.contains(&result.book_index)
+  }
 }
 
 fn draw_table(
   frame: &mut Frame,
   area: Rect,
-  books: &[Book],
-  results: &[SearchResult],
+  list: &BookList<'_>,
   table_state: &mut TableState,
-  selected_book_indices: &BTreeSet<usize>,
-  layout: &Layout,
-  theme: &Theme,
-) {
+) -> TableGeometry {
+  let BookList {
+    books,
+    results,
+    rows,
+    layout,
+    theme,
+    ..
+  } = *list;
   let columns = layout.visible_columns().collect::<Vec<_>>();
-  let header = Row::new(columns.iter().map(|column| {
-    Cell::from(column.label.clone()).style(
+  let (block, inner) = list.frame_block(area);
+  let cell_widths = column_widths(&columns, inner.width);
+  let widths = cell_widths
+    .iter()
+    .map(|width| Constraint::Length(*width))
+    .collect::<Vec<_>>();
+
+  let header = Row::new(columns.iter().zip(&cell_widths).map(|(column, width)| {
+    let label = fit_line(Line::from(column.label.clone()), *width, column.ellipsis);
+    Cell::from(align_line(label, column.align)).style(
       Style::default()
         .fg(theme.color(&theme.table.header))
         .bg(theme.color(&theme.background))
@@ -294,91 +671,633 @@ fn draw_table(
   .height(1)
   .bottom_margin(1);
 
+  let header_height = inner.height.min(2);
+  let body = Rect {
+    y: inner.y + header_height,
+    height: inner.height - header_height,
+    ..inner
+  };
+  let (window, mut window_state) = row_window(table_state, rows.len(), usize::from(body.height));
   let selected = table_state.selected();
-  let rows = results.iter().enumerate().map(|(row_index, result)| {
+  let table_rows = window.clone().map(|row_index| {
+    let row = &rows.rows[row_index];
+    let result = match *row {
+      TableRow::Group(group_index) => {
+        return group_header_row(
+          &rows.groups[group_index],
+          columns.len(),
+          selected == Some(row_index),
+          theme,
+        );
+      }
+      TableRow::Result(result_index) => &results[result_index],
+    };
     let book = &books[result.book_index];
     let is_hovered = selected == Some(row_index);
-    let is_marked = selected_book_indices.contains(&result.book_index);
+    let is_marked = list.is_marked(result);
 
-    Row::new(columns.iter().map(|column| {
-      let (text, ranges) = field_text_and_highlights(book, &result.highlights, column.field);
-      let base_style = if is_marked && is_hovered {
-        Style::default()
-          .fg(theme.color(&theme.row.selected_hover_foreground))
-          .bg(theme.color(&theme.row.selected_hover_background))
-      } else if is_marked {
-        Style::default()
-          .fg(theme.color(&theme.row.selected_foreground))
-          .bg(theme.color(&theme.row.selected_background))
-      } else if is_hovered {
-        Style::default()
-          .fg(theme.color(&theme.row.hover_foreground))
-          .bg(theme.color(&theme.row.hover_background))
-      } else {
-        Style::default()
-          .fg(field_color(column.field, theme))
-          .bg(theme.color(&theme.background))
-      };
-      let highlight_style = if is_marked && is_hovered {
-        Style::default()
-          .fg(theme.color(&theme.highlight.selected_hover))
-          .bg(theme.color(&theme.row.selected_hover_background))
-          .add_modifier(Modifier::BOLD)
-      } else if is_marked {
-        Style::default()
-          .fg(theme.color(&theme.highlight.selected))
-          .bg(theme.color(&theme.row.selected_background))
-          .add_modifier(Modifier::BOLD)
-      } else if is_hovered {
-        Style::default()
-          .fg(theme.color(&theme.highlight.hover))
-          .bg(theme.color(&theme.row.hover_background))
-          .add_modifier(Modifier::BOLD)
-      } else {
-        Style::default()
-          .fg(theme.color(&theme.highlight.normal))
-          .bg(theme.color(&theme.background))
-          .add_modifier(Modifier::BOLD)
-      };
-
-      Cell::from(highlighted_line(&text, ranges, base_style, highlight_style)).style(base_style)
+    Row::new(columns.iter().zip(&cell_widths).map(|(column, width)| {
+      let (text, ranges) = field_text_and_highlights(book, &result.highlights, column);
+      let (base_style, highlight_style) = row_styles(
+        theme,
+        is_marked,
+        is_hovered,
+        column_color(&column.key, theme),
+      );
+      let line = highlighted_line(&text, &ranges, base_style, highlight_style);
+      Cell::from(align_line(
+        fit_line(line, *width, column.ellipsis),
+        column.align,
+      ))
+      .style(base_style)
     }))
     .height(1)
   });
 
-  let total_width = columns
-    .iter()
-    .map(|column| u32::from(column.width))
-    .sum::<u32>();
-  let widths = columns
-    .iter()
-    .map(|column| Constraint::Ratio(u32::from(column.width), total_width))
-    .collect::<Vec<_>>();
+  // Same split as the table widget, which has no highlight symbol column.
+  let column_areas = TuiLayout::horizontal(widths.clone())
+    .flex(Flex::Start)
+    .split(inner);
 
-  let table = Table::new(rows, widths)
+  let table = Table::new(table_rows, widths)
     .header(header)
-    .block(
-      Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme.color(&theme.table.border)))
-        .border_type(BorderType::Rounded)
-        .title(Span::styled(
-          " Book List ",
-          Style::default().fg(theme.color(&theme.table.title)),
-        )),
-    )
+    .block(block)
+    .column_spacing(0)
+    .flex(Flex::Start)
+    .row_highlight_style(Style::default());
+
+  frame.render_stateful_widget(table, area, &mut window_state);
+  *table_state.offset_mut() = window.start + window_state.offset();
+
+  TableGeometry {
+    header_y: inner.y,
+    body,
+    offset: table_state.offset(),
+    columns: column_areas
+      .iter()
+      .map(|column| (column.x, column.width))
+      .collect(),
+    row_height: 1,
+    grid_columns: 1,
+    tile_width: body.width,
+  }
+}
+
+/// Rows to build for a list that shows `visible` full rows: the shown ones plus a margin,
+/// so large result sets only style what is on screen. Returns the row range and a state
+/// relative to it. The offset moves just enough to keep the focused row visible, like the
+/// table widget's own scrolling.
+fn row_window(table_state: &TableState, len: usize, visible: usize) -> (Range<usize>, TableState) {
+  let visible = visible.max(1);
+  let last_row = len.saturating_sub(1);
+  let mut offset = table_state.offset().min(last_row);
+  let selected = table_state
+    .selected()
+    .map(|selected| selected.min(last_row));
+  if let Some(selected) = selected {
+    if selected < offset {
+      offset = selected;
+    } else if selected >= offset + visible {
+      offset = selected + 1 - visible;
+    }
+  }
+
+  let start = offset.saturating_sub(WINDOW_MARGIN);
+  let end = (offset + visible + WINDOW_MARGIN).min(len);
+  let state = TableState::default()
+    .with_offset(offset - start)
+    .with_selected(selected.map(|selected| selected - start));
+  (start..end, state)
+}
+
+/// Two-line entries: the title, then authors, series, and tags.
+fn draw_cards(
+  frame: &mut Frame,
+  area: Rect,
+  list: &BookList<'_>,
+  table_state: &mut TableState,
+) -> TableGeometry {
+  let BookList {
+    books,
+    results,
+    rows,
+    theme,
+    ..
+  } = *list;
+  let (block, inner) = list.frame_block(area);
+  let (window, mut window_state) = row_window(
+    table_state,
+    rows.len(),
+    usize::from(inner.height / CARD_HEIGHT),
+  );
+  let selected = table_state.selected();
+  let card_rows = window.clone().map(|row_index| {
+    let row = &rows.rows[row_index];
+    let result = match *row {
+      TableRow::Group(group_index) => {
+        return group_header_row(
+          &rows.groups[group_index],
+          1,
+          selected == Some(row_index),
+          theme,
+        )
+        .height(CARD_HEIGHT);
+      }
+      TableRow::Result(result_index) => &results[result_index],
+    };
+    let book = &books[result.book_index];
+    let is_hovered = selected == Some(row_index);
+    let is_marked = list.is_marked(result);
+    let (title, details) = book_lines(list, book, result, is_marked, is_hovered);
+    let (base_style, _) = row_styles(theme, is_marked, is_hovered, theme.color(&theme.foreground));
+    Row::new([Cell::from(Text::from(vec![
+      fit_line(title, inner.width, Ellipsis::End),
+      fit_line(details, inner.width, Ellipsis::End),
+    ]))
+    .style(base_style)])
+    .height(CARD_HEIGHT)
+  });
+
+  let table = Table::new(card_rows, [Constraint::Fill(1)])
+    .block(block)
     .column_spacing(0)
     .row_highlight_style(Style::default());
+  frame.render_stateful_widget(table, area, &mut window_state);
+  *table_state.offset_mut() = window.start + window_state.offset();
+
+  TableGeometry {
+    header_y: inner.y,
+    body: inner,
+    offset: table_state.offset(),
+    columns: Vec::new(),
+    row_height: CARD_HEIGHT,
+    grid_columns: 1,
+    tile_width: inner.width,
+  }
+}
+
+/// Bordered tiles with the title and authors, filled left to right. The table state
+/// offset holds the first shown tile.
+fn draw_grid(
+  frame: &mut Frame,
+  area: Rect,
+  list: &BookList<'_>,
+  table_state: &mut TableState,
+  mut covers: Option<&mut CoverCache>,
+) -> TableGeometry {
+  let BookList {
+    books,
+    results,
+    rows,
+    theme,
+    ..
+  } = *list;
+  let (block, inner) = list.frame_block(area);
+  frame.render_widget(block, area);
 
-  frame.render_stateful_widget(table, area, table_state);
+  let cover_height = if covers.is_some() { COVER_HEIGHT } else { 0 };
+  let tile_height = TILE_HEIGHT + cover_height;
+  let grid_columns = usize::from((inner.width / TILE_MIN_WIDTH).max(1));
+  let tile_width = inner.width / grid_columns as u16;
+  let lines = usize::from((inner.height / tile_height).max(1));
+  let selected = table_state.selected();
+  let selected_line = selected.unwrap_or(0) / grid_columns;
+  let mut first_line = table_state.offset() / grid_columns;
+  if selected_line < first_line {
+    first_line = selected_line;
+  } else if selected_line >= first_line + lines {
+    first_line = selected_line + 1 - lines;
+  }
+  let offset = first_line * grid_columns;
+  *table_state.offset_mut() = offset;
+
+  for (position, (row_index, row)) in rows
+    .rows
+    .iter()
+    .enumerate()
+    .skip(offset)
+    .take(lines * grid_columns)
+    .enumerate()
+  {
+    let tile = Rect {
+      x: inner.x + (position % grid_columns) as u16 * tile_width,
+      y: inner.y + (position / grid_columns) as u16 * tile_height,
+      width: tile_width,
+      height: tile_height,
+    };
+    let is_hovered = selected == Some(row_index);
+    let (tile_lines, style, border_color, cover) = match *row {
+      TableRow::Group(group_index) => {
+        let group = &rows.groups[group_index];
+        let marker = if group.collapsed { "▸" } else { "▾" };
+        let value = if group.value.is_empty() {
+          "(none)"
+        } else {
+          group.value.as_str()
+        };
+        let (style, _) = row_styles(
+          theme,
+          false,
+          is_hovered,
+          theme.color(&theme.table.group_header),
+        );
+        let label = Line::from(format!("{marker} {value}"));
+        let count = Line::from(format!("{} books", group.len));
+        (
+          vec![label, count],
+          style.add_modifier(Modifier::BOLD),
+          theme.color(&theme.table.group_header),
+          None,
+        )
+      }
+      TableRow::Result(result_index) => {
+        let result = &results[result_index];
+        let book = &books[result.book_index];
+        let is_marked = list.is_marked(result);
+        let (title, _) = book_lines(list, book, result, is_marked, is_hovered);
+        let authors_column = LayoutColumn::field(BookField::Authors);
+        let (authors, authors_ranges) =
+          field_text_and_highlights(book, &result.highlights, &authors_column);
+        let (style, highlight_style) = row_styles(
+          theme,
+          is_marked,
+          is_hovered,
+          theme.color(&theme.table.authors_field),
+        );
+        (
+          vec![
+            title,
+            highlighted_line(&authors, &authors_ranges, style, highlight_style),
+          ],
+          row_styles(theme, is_marked, is_hovered, theme.color(&theme.foreground)).0,
+          theme.color(&theme.table.border),
+          book.cover.as_deref().map(|path| (result.book_index, path)),
+        )
+      }
+    };
+    let border_color = if is_hovered {
+      theme.color(&theme.accent)
+    } else {
+      border_color
+    };
+    let tile_block = Block::default()
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded)
+      .border_style(Style::default().fg(border_color))
+      .style(style);
+    let tile_inner = tile_block.inner(tile);
+    frame.render_widget(tile_block, tile);
+    let cover_area = Rect {
+      height: cover_height.min(tile_inner.height),
+      ..tile_inner
+    };
+    if let (Some(covers), Some((book_index, path))) = (covers.as_deref_mut(), cover)
+      && let Some(thumbnail) =
+        covers.thumbnail(book_index, path, (cover_area.width, cover_area.height))
+    {
+      frame.render_widget(thumbnail, cover_area);
+    }
+    let text_area = Rect {
+      y: tile_inner.y + cover_area.height,
+      height: tile_inner.height - cover_area.height,
+      ..tile_inner
+    };
+    let text = tile_lines
+      .into_iter()
+      .map(|line| fit_line(line, text_area.width, Ellipsis::End))
+      .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(text), text_area);
+  }
+
+  TableGeometry {
+    header_y: inner.y,
+    body: inner,
+    offset,
+    columns: Vec::new(),
+    row_height: tile_height,
+    grid_columns,
+    tile_width,
+  }
 }
 
-fn field_text_and_highlights<'a>(
-  book: &'a Book,
-  highlights: &'a BookHighlights,
-  field: BookField,
-) -> (String, &'a HighlightRanges) {
-  (field_text(book, field), highlights.ranges(field))
+/// The title line and the `authors · series · tags` line of a book, with search highlights.
+/// Layout columns for these fields supply their display options.
+fn book_lines(
+  list: &BookList<'_>,
+  book: &Book,
+  result: &SearchResult,
+  is_marked: bool,
+  is_hovered: bool,
+) -> (Line<'static>, Line<'static>) {
+  let theme = list.theme;
+  let field_line = |field: BookField| {
+    let key = ColumnKey::Field(field);
+    let column = list
+      .layout
+      .column(&key)
+      .cloned()
+      .unwrap_or_else(|| LayoutColumn::field(field));
+    let (mut text, ranges) = field_text_and_highlights(book, &result.highlights, &column);
+    if field == BookField::Series && !text.is_empty() {
+      text.push_str(&format!(" #{}", format_series_index(book.series_index)));
+    }
+    let (base_style, highlight_style) =
+      row_styles(theme, is_marked, is_hovered, column_color(&key, theme));
+    (
+      text.is_empty(),
+      highlighted_line(&text, &ranges, base_style, highlight_style),
+    )
+  };
+
+  let (_, title) = field_line(BookField::Title);
+  let title = title.patch_style(Modifier::BOLD);
+  let (separator_style, _) = row_styles(theme, is_marked, is_hovered, theme.color(&theme.muted));
+  let mut details = Vec::new();
+  for field in [BookField::Authors, BookField::Series, BookField::Tags] {
+    let (is_empty, line) = field_line(field);
+    if is_empty {
+      continue;
+    }
+    if !details.is_empty() {
+      details.push(Span::styled(" · ", separator_style));
+    }
+    details.extend(line.spans);
+  }
+  (title, Line::from(details))
+}
+
+/// Base and highlight styles of a book row or tile. `foreground` is used when the row is
+/// neither focused nor selected.
+fn row_styles(
+  theme: &Theme,
+  is_marked: bool,
+  is_hovered: bool,
+  foreground: Color,
+) -> (Style, Style) {
+  let (base_style, highlight_color) = if is_marked && is_hovered {
+    (
+      Style::default()
+        .fg(theme.color(&theme.row.selected_hover_foreground))
+        .bg(theme.color(&theme.row.selected_hover_background)),
+      &theme.highlight.selected_hover,
+    )
+  } else if is_marked {
+    (
+      Style::default()
+        .fg(theme.color(&theme.row.selected_foreground))
+        .bg(theme.color(&theme.row.selected_background)),
+      &theme.highlight.selected,
+    )
+  } else if is_hovered {
+    (
+      Style::default()
+        .fg(theme.color(&theme.row.hover_foreground))
+        .bg(theme.color(&theme.row.hover_background)),
+      &theme.highlight.hover,
+    )
+  } else {
+    (
+      Style::default()
+        .fg(foreground)
+        .bg(theme.color(&theme.background)),
+      &theme.highlight.normal,
+    )
+  };
+  let highlight_style = base_style
+    .fg(theme.color(highlight_color))
+    .add_modifier(Modifier::BOLD);
+  (base_style, highlight_style)
+}
+
+/// Cell widths of the visible columns. Fixed columns take their width first, and the rest
+/// is shared by ratio, with columns that would fall below `min_width` raised to it.
+fn column_widths(columns: &[&LayoutColumn], available: u16) -> Vec<u16> {
+  let mut widths = vec![0; columns.len()];
+  let mut remaining = available;
+  for (width, column) in widths.iter_mut().zip(columns) {
+    if column.fixed {
+      *width = column.width.min(remaining);
+      remaining -= *width;
+    }
+  }
+
+  let mut shared = (0..columns.len())
+    .filter(|index| !columns[*index].fixed)
+    .collect::<Vec<_>>();
+  while !shared.is_empty() {
+    let total = shared
+      .iter()
+      .map(|index| u32::from(columns[*index].width))
+      .sum::<u32>()
+      .max(1);
+    let share =
+      |index: usize| (u32::from(remaining) * u32::from(columns[index].width) / total) as u16;
+    let raised = shared
+      .iter()
+      .copied()
+      .filter(|index| share(*index) < columns[*index].min_width)
+      .collect::<Vec<_>>();
+    if raised.is_empty() {
+      for index in &shared {
+        widths[*index] = share(*index);
+      }
+      let used = shared.iter().map(|index| widths[*index]).sum::<u16>();
+      for index in shared.iter().take(usize::from(remaining - used)) {
+        widths[*index] += 1;
+      }
+      break;
+    }
+    for index in raised {
+      widths[index] = columns[index].min_width.min(remaining);
+      remaining -= widths[index];
+      shared.retain(|shared_index| *shared_index != index);
+    }
+  }
+  widths
+}
+
+/// Cuts a line to `width` cells, replacing the cut text with `…`. Double-width characters
+/// are never split, so the result can be one cell narrower.
+fn fit_line(line: Line<'static>, width: u16, ellipsis: Ellipsis) -> Line<'static> {
+  let width = usize::from(width);
+  if line.width() <= width {
+    return line;
+  }
+  if width == 0 {
+    return Line::default();
+  }
+
+  let (mut spans, head_width) = match ellipsis {
+    Ellipsis::End => take_width(&line.spans, width - 1),
+    Ellipsis::Middle => take_width(&line.spans, (width - 1).div_ceil(2)),
+  };
+  spans.push(Span::raw("…"));
+  if ellipsis == Ellipsis::Middle {
+    let reversed = reversed_spans(&line.spans);
+    let (tail, _) = take_width(&reversed, width - 1 - head_width);
+    spans.extend(reversed_spans(&tail));
+  }
+  Line::from(spans)
+}
+
+/// Leading spans that fit in `width` cells, and the width they use.
+fn take_width(spans: &[Span<'static>], width: usize) -> (Vec<Span<'static>>, usize) {
+  let mut taken = Vec::new();
+  let mut used = 0;
+  'spans: for span in spans {
+    let mut text = String::new();
+    for ch in span.content.chars() {
+      let ch_width = ch.width().unwrap_or(0);
+      if used + ch_width > width {
+        taken.push(Span::styled(text, span.style));
+        break 'spans;
+      }
+      used += ch_width;
+      text.push(ch);
+    }
+    taken.push(Span::styled(text, span.style));
+  }
+  (taken, used)
+}
+
+/// Spans in reverse order with reversed text, used to take text from the end of a line.
+fn reversed_spans(spans: &[Span<'static>]) -> Vec<Span<'static>> {
+  spans
+    .iter()
+    .rev()
+    .map(|span| Span::styled(span.content.chars().rev().collect::<String>(), span.style))
+    .collect()
+}
+
+fn align_line(line: Line<'static>, align: ColumnAlign) -> Line<'static> {
+  match align {
+    ColumnAlign::Left => line.left_aligned(),
+    ColumnAlign::Right => line.right_aligned(),
+    ColumnAlign::Center => line.centered(),
+  }
+}
+
+fn group_header_row(
+  group: &ResultGroup,
+  column_count: usize,
+  is_hovered: bool,
+  theme: &Theme,
+) -> Row<'static> {
+  let style = if is_hovered {
+    Style::default()
+      .fg(theme.color(&theme.row.hover_foreground))
+      .bg(theme.color(&theme.row.hover_background))
+  } else {
+    Style::default()
+      .fg(theme.color(&theme.table.group_header))
+      .bg(theme.color(&theme.background))
+  }
+  .add_modifier(Modifier::BOLD);
+  let marker = if group.collapsed { "▸" } else { "▾" };
+  let value = if group.value.is_empty() {
+    "(none)"
+  } else {
+    group.value.as_str()
+  };
+  let label = format!("{marker} {value} ({})", group.len);
+
+  Row::new(
+    std::iter::once(Cell::from(label))
+      .chain((1..column_count).map(|_| Cell::from("")))
+      .collect::<Vec<_>>(),
+  )
+  .style(style)
+  .height(1)
+}
+
+fn field_text_and_highlights(
+  book: &Book,
+  highlights: &BookHighlights,
+  column: &LayoutColumn,
+) -> (String, HighlightRanges) {
+  let ranges = highlights.column_ranges(&column.key);
+  if let Some((values, separator)) = column
+    .key
+    .field()
+    .and_then(|field| list_values(book, field))
+    && (column.leaf_names || column.separator.is_some() || column.max_values > 0)
+  {
+    return list_text_and_highlights(values, separator, column, ranges);
+  }
+  let text = match (&column.template, column.key.field()) {
+    (Some(template), _) => template.render(book),
+    (None, Some(field)) => field_text(book, field),
+    (None, None) => String::new(),
+  };
+  (text, ranges.clone())
+}
+
+/// List values joined with the column's separator, reduced to leaf tag names and cut
+/// after `max_values` as set on the column. Highlight ranges are moved from the default
+/// joined text, and matches inside hidden text are dropped.
+fn list_text_and_highlights(
+  values: &[String],
+  default_separator: &str,
+  column: &LayoutColumn,
+  ranges: &HighlightRanges,
+) -> (String, HighlightRanges) {
+  let separator = column.separator.as_deref().unwrap_or(default_separator);
+  let shown = match column.max_values {
+    0 => values.len(),
+    max_values => values.len().min(max_values),
+  };
+  // Ranges count non-whitespace chars, so only visible separator chars move them.
+  let full_separator_len = non_whitespace_len(default_separator);
+  let separator_len = non_whitespace_len(separator);
+  let mut full_start = 0;
+  let mut start = 0;
+  let mut text = String::new();
+  let mut shown_ranges = Vec::new();
+  for (index, value) in values[..shown].iter().enumerate() {
+    let shown_value = if column.leaf_names {
+      tag_leaf(value)
+    } else {
+      value
+    };
+    let full_len = non_whitespace_len(value);
+    let len = non_whitespace_len(shown_value);
+    let visible_start = full_start + full_len - len;
+    for (range_start, range_end) in ranges {
+      let range_start = (*range_start).max(visible_start);
+      let range_end = (*range_end).min(full_start + full_len);
+      if range_start < range_end {
+        shown_ranges.push((
+          range_start - visible_start + start,
+          range_end - visible_start + start,
+        ));
+      }
+    }
+    if index > 0 {
+      text.push_str(separator);
+    }
+    text.push_str(shown_value);
+    full_start += full_len + full_separator_len;
+    start += len + separator_len;
+  }
+  if shown < values.len() {
+    text.push_str(&format!(" +{} more", values.len() - shown));
+  }
+  (text, shown_ranges)
+}
+
+/// Values of list fields with their default separator.
+fn list_values(book: &Book, field: BookField) -> Option<(&[String], &'static str)> {
+  match field {
+    BookField::Authors => Some((&book.authors, " & ")),
+    BookField::Formats => Some((&book.formats, ", ")),
+    BookField::Tags => Some((&book.tags, ", ")),
+    BookField::Languages => Some((&book.languages, ", ")),
+    BookField::Title | BookField::Series | BookField::Publisher => None,
+  }
+}
+
+fn non_whitespace_len(text: &str) -> usize {
+  text.chars().filter(|ch| !ch.is_whitespace()).count()
 }
 
 fn field_text(book: &Book, field: BookField) -> String {
@@ -388,16 +1307,44 @@ fn field_text(book: &Book, field: BookField) -> String {
     BookField::Series => book.series.clone(),
     BookField::Formats => book.formats.join(", "),
     BookField::Tags => book.tags.join(", "),
+    BookField::Publisher => book.publisher.clone(),
+    BookField::Languages => book.languages.join(", "),
+  }
+}
+
+impl DisplayMode {
+  pub const NAMES: &[&str] = &["table", "card", "grid"];
+
+  pub fn parse(input: &str) -> Option<Self> {
+    match input.to_ascii_lowercase().as_str() {
+      "table" => Some(Self::Table),
+      "card" | "cards" => Some(Self::Card),
+      "grid" => Some(Self::Grid),
+      _ => None,
+    }
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Table => "table",
+      Self::Card => "card",
+      Self::Grid => "grid",
+    }
   }
 }
 
-fn field_color(field: BookField, theme: &Theme) -> Color {
+fn column_color(column: &ColumnKey, theme: &Theme) -> Color {
+  let ColumnKey::Field(field) = column else {
+    return theme.color(&theme.table.template_field);
+  };
   match field {
     BookField::Title => theme.color(&theme.table.title_field),
     BookField::Authors => theme.color(&theme.table.authors_field),
     BookField::Series => theme.color(&theme.table.series_field),
     BookField::Formats => theme.color(&theme.table.formats_field),
     BookField::Tags => theme.color(&theme.table.tags_field),
+    BookField::Publisher => theme.color(&theme.table.publisher_field),
+    BookField::Languages => theme.color(&theme.table.languages_field),
   }
 }
 
@@ -2,10 +2,44 @@ use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub struct Book {
+  pub id: i64,
   pub path: PathBuf,
   pub title: String,
+  pub title_sort: String,
   pub authors: Vec<String>,
+  pub author_sort: String,
   pub series: String,
+  pub series_index: f64,
   pub formats: Vec<String>,
   pub tags: Vec<String>,
+  pub publisher: String,
+  pub languages: Vec<String>,
+  /// Publication date as stored by Calibre, such as `2019-05-01 12:00:00+00:00`.
+  pub pubdate: String,
+  /// Date the book was added to the library, in the same format.
+  pub timestamp: String,
+  /// Calibre's `cover.jpg` of the book, when it has a cover.
+  pub cover: Option<PathBuf>,
+}
+
+/// Separator of Calibre's hierarchical tags, as in `Fiction.Science Fiction`.
+pub const TAG_SEPARATOR: char = '.';
+
+/// The last part of a hierarchical tag.
+pub fn tag_leaf(tag: &str) -> &str {
+  tag.rsplit(TAG_SEPARATOR).next().unwrap_or(tag)
+}
+
+/// The parent of a hierarchical tag, if it has one.
+pub fn tag_parent(tag: &str) -> Option<&str> {
+  tag.rsplit_once(TAG_SEPARATOR).map(|(parent, _)| parent)
+}
+
+/// A tag and each of its parents, from the top level down.
+pub fn tag_paths(tag: &str) -> Vec<&str> {
+  tag
+    .match_indices(TAG_SEPARATOR)
+    .map(|(index, _)| &tag[..index])
+    .chain(std::iter::once(tag))
+    .collect()
 }
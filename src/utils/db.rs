@@ -1,6 +1,7 @@
 use crate::utils::book::Book;
 use anyhow::{Context, Result};
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
 use std::path::{Path, PathBuf};
 
 /// load book list from calibre metadata.db
@@ -12,12 +13,22 @@ pub fn load_books_from_db(library_path: &Path) -> Result<Vec<Book>> {
   let mut stmt = conn.prepare(
         "
         SELECT
+            b.id AS id,
             b.title AS title,
+            b.sort AS title_sort,
+            b.author_sort AS author_sort,
             b.path || '/' || (SELECT name FROM data WHERE book = b.id ORDER BY id DESC LIMIT 1) || '.' || lower((SELECT format FROM data WHERE book = b.id ORDER BY id DESC LIMIT 1)) AS relative_path,
             (SELECT GROUP_CONCAT(a.name, '&') FROM authors a JOIN books_authors_link bal ON a.id = bal.author WHERE bal.book = b.id) AS authors,
             s.name AS series,
+            b.series_index AS series_index,
+            b.pubdate AS pubdate,
+            b.timestamp AS timestamp,
+            b.path AS book_dir,
+            b.has_cover AS has_cover,
             (SELECT GROUP_CONCAT(d.format, ',') FROM data d WHERE d.book = b.id) AS formats,
-            (SELECT GROUP_CONCAT(t.name, ',') FROM tags t JOIN books_tags_link btl ON t.id = btl.tag WHERE btl.book = b.id) AS tags
+            (SELECT GROUP_CONCAT(t.name, ',') FROM tags t JOIN books_tags_link btl ON t.id = btl.tag WHERE btl.book = b.id) AS tags,
+            (SELECT p.name FROM publishers p JOIN books_publishers_link bpl ON p.id = bpl.publisher WHERE bpl.book = b.id) AS publisher,
+            (SELECT GROUP_CONCAT(l.lang_code, ',') FROM languages l JOIN books_languages_link bll ON l.id = bll.lang_code WHERE bll.book = b.id) AS languages
         FROM
             books b
         LEFT JOIN
@@ -30,9 +41,16 @@ pub fn load_books_from_db(library_path: &Path) -> Result<Vec<Book>> {
     )?;
 
   let book_iter = stmt.query_map([], |row| {
+    let id: i64 = row.get("id")?;
     let title: String = row
       .get::<&str, Option<String>>("title")?
       .unwrap_or_default();
+    let title_sort: String = row
+      .get::<&str, Option<String>>("title_sort")?
+      .unwrap_or_default();
+    let author_sort: String = row
+      .get::<&str, Option<String>>("author_sort")?
+      .unwrap_or_default();
     let relative_path: String = row
       .get::<&str, Option<String>>("relative_path")?
       .unwrap_or_default();
@@ -41,9 +59,21 @@ pub fn load_books_from_db(library_path: &Path) -> Result<Vec<Book>> {
     } else {
       library_path.join(&relative_path)
     };
+    let has_cover = row.get::<&str, Option<bool>>("has_cover")?.unwrap_or(false);
+    let cover = row
+      .get::<&str, Option<String>>("book_dir")?
+      .filter(|book_dir| has_cover && !book_dir.is_empty())
+      .map(|book_dir| library_path.join(book_dir).join("cover.jpg"));
     let series: String = row
       .get::<&str, Option<String>>("series")?
       .unwrap_or_default();
+    let series_index: f64 = row.get::<&str, Option<f64>>("series_index")?.unwrap_or(1.0);
+    let pubdate: String = row
+      .get::<&str, Option<String>>("pubdate")?
+      .unwrap_or_default();
+    let timestamp: String = row
+      .get::<&str, Option<String>>("timestamp")?
+      .unwrap_or_default();
     let formats: Vec<String> = row
       .get::<&str, Option<String>>("formats")?
       .unwrap_or_default()
@@ -58,6 +88,16 @@ pub fn load_books_from_db(library_path: &Path) -> Result<Vec<Book>> {
       .map(|s| s.trim().to_string())
       .filter(|s| !s.is_empty())
       .collect();
+    let publisher: String = row
+      .get::<&str, Option<String>>("publisher")?
+      .unwrap_or_default();
+    let languages: Vec<String> = row
+      .get::<&str, Option<String>>("languages")?
+      .unwrap_or_default()
+      .split(',')
+      .map(|s| s.trim().to_string())
+      .filter(|s| !s.is_empty())
+      .collect();
 
     let authors: Vec<String> = row
       .get::<&str, Option<String>>("authors")?
@@ -68,12 +108,21 @@ pub fn load_books_from_db(library_path: &Path) -> Result<Vec<Book>> {
       .collect();
 
     let book = Book {
+      id,
       path: full_path,
       title,
+      title_sort,
       authors,
+      author_sort,
       series,
+      series_index,
       formats,
       tags,
+      publisher,
+      languages,
+      pubdate,
+      timestamp,
+      cover,
     };
 
     Ok(book)
@@ -81,3 +130,38 @@ pub fn load_books_from_db(library_path: &Path) -> Result<Vec<Book>> {
 
   Ok(book_iter.collect::<Result<Vec<_>, _>>()?)
 }
+
+/// Reads a JSON value from Calibre's `preferences` table. Returns `None` when the key or the
+/// table is missing.
+pub fn load_calibre_preference<T: DeserializeOwned>(
+  library_path: &Path,
+  key: &str,
+) -> Result<Option<T>> {
+  let db_path = library_path.join("metadata.db");
+  let conn = Connection::open(&db_path)
+    .with_context(|| format!("Failed to open Calibre database: {:?}", db_path))?;
+
+  let has_table = conn
+    .query_row(
+      "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'preferences'",
+      [],
+      |_| Ok(()),
+    )
+    .optional()?
+    .is_some();
+  if !has_table {
+    return Ok(None);
+  }
+
+  let value = conn
+    .query_row("SELECT val FROM preferences WHERE key = ?1", [key], |row| {
+      row.get::<_, String>(0)
+    })
+    .optional()?;
+  value
+    .map(|value| {
+      serde_json::from_str(&value)
+        .with_context(|| format!("invalid JSON in Calibre preference '{key}'"))
+    })
+    .transpose()
+}